@@ -0,0 +1,857 @@
+// In src-tauri/src/backend.rs
+
+use crate::shortcuts::{self, BackendEntry, OllamaProfile, UnifiedShortcutState};
+use crate::{AppSettings, HttpClientState};
+use axum::body::Bytes;
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method, Request, Uri};
+use http_body_util::BodyExt;
+use reqwest::{RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+
+const MAX_LATENCY_SAMPLES: u32 = 100;
+// Loading a large model from disk can take a while
+const MODEL_LOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+// Attaches the active backend's API key, mirroring what the proxy injects
+fn with_api_key(app_handle: &AppHandle, request: RequestBuilder) -> RequestBuilder {
+    let base_url = crate::ollama_base_url(app_handle);
+    with_api_key_for(app_handle, &base_url, request)
+}
+
+// Attaches the API key for the backend at `base_url`
+fn with_api_key_for(
+    app_handle: &AppHandle,
+    base_url: &str,
+    request: RequestBuilder,
+) -> RequestBuilder {
+    match crate::ollama_api_key_for(app_handle, base_url) {
+        Some(api_key) => request.bearer_auth(api_key),
+        None => request,
+    }
+}
+
+#[derive(Serialize)]
+pub struct LatencyStats {
+    samples: usize,
+    failures: usize,
+    min_ms: f64,
+    max_ms: f64,
+    avg_ms: f64,
+    p50_ms: f64,
+    p95_ms: f64,
+}
+
+// Nearest-rank percentile over an already sorted slice
+fn percentile(sorted_ms: &[f64], pct: f64) -> f64 {
+    let index = ((pct / 100.0) * (sorted_ms.len() - 1) as f64).round() as usize;
+    sorted_ms[index]
+}
+
+/// Issues `sample_count` requests to the backend's `/api/tags` through the shared
+/// proxy client and reports round-trip latency statistics in milliseconds
+#[tauri::command]
+pub async fn measure_proxy_latency(
+    sample_count: u32,
+    http_client: State<'_, HttpClientState>,
+    app_handle: AppHandle,
+) -> Result<LatencyStats, String> {
+    if sample_count == 0 || sample_count > MAX_LATENCY_SAMPLES {
+        return Err(format!(
+            "sample_count must be between 1 and {}",
+            MAX_LATENCY_SAMPLES
+        ));
+    }
+
+    let client = http_client.client();
+    let target_url = format!("{}/api/tags", crate::ollama_base_url(&app_handle));
+    log::info!(
+        "Measuring proxy latency to {} with {} samples",
+        target_url,
+        sample_count
+    );
+
+    let mut samples_ms = Vec::with_capacity(sample_count as usize);
+    let mut failures = 0;
+
+    for _ in 0..sample_count {
+        let started = Instant::now();
+        let result = with_api_key(&app_handle, client.get(&target_url))
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await;
+
+        match result {
+            // Read the full body so the sample covers the whole round trip
+            Ok(response) if response.status().is_success() => match response.bytes().await {
+                Ok(_) => samples_ms.push(started.elapsed().as_secs_f64() * 1000.0),
+                Err(e) => {
+                    log::warn!("Latency sample failed reading body: {}", e);
+                    failures += 1;
+                }
+            },
+            Ok(response) => {
+                log::warn!("Latency sample failed: Status {}", response.status());
+                failures += 1;
+            }
+            Err(e) => {
+                log::warn!("Latency sample failed: {}", e);
+                failures += 1;
+            }
+        }
+    }
+
+    if samples_ms.is_empty() {
+        return Err(format!(
+            "All {} latency samples to {} failed",
+            sample_count, target_url
+        ));
+    }
+
+    samples_ms.sort_by(|a, b| a.total_cmp(b));
+    let stats = LatencyStats {
+        samples: samples_ms.len(),
+        failures,
+        min_ms: samples_ms[0],
+        max_ms: samples_ms[samples_ms.len() - 1],
+        avg_ms: samples_ms.iter().sum::<f64>() / samples_ms.len() as f64,
+        p50_ms: percentile(&samples_ms, 50.0),
+        p95_ms: percentile(&samples_ms, 95.0),
+    };
+
+    log::info!(
+        "Proxy latency: avg {:.1}ms, p50 {:.1}ms, p95 {:.1}ms",
+        stats.avg_ms,
+        stats.p50_ms,
+        stats.p95_ms
+    );
+
+    Ok(stats)
+}
+
+// Sends `model` an empty generate request, which loads it if it isn't resident.
+// Returns the round-trip time in milliseconds
+async fn timed_generate(
+    app_handle: &AppHandle,
+    client: &reqwest::Client,
+    model: &str,
+) -> Result<u64, String> {
+    let target_url = format!("{}/api/generate", crate::ollama_base_url(app_handle));
+
+    let started = Instant::now();
+    let response = with_api_key(app_handle, client.post(&target_url))
+        .json(&serde_json::json!({ "model": model }))
+        .timeout(MODEL_LOAD_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to load model '{}': {}", model, e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "Failed to load model '{}': Status {} {}",
+            model, status, body
+        ));
+    }
+
+    Ok(started.elapsed().as_millis() as u64)
+}
+
+// Sends `model` a generate request with keep_alive=0, evicting it from memory
+async fn evict_model(
+    app_handle: &AppHandle,
+    client: &reqwest::Client,
+    model: &str,
+) -> Result<(), String> {
+    let target_url = format!("{}/api/generate", crate::ollama_base_url(app_handle));
+
+    let response = with_api_key(app_handle, client.post(&target_url))
+        .json(&serde_json::json!({ "model": model, "keep_alive": 0 }))
+        .timeout(Duration::from_secs(30))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to unload model '{}': {}", model, e))?;
+
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+
+    // Ollama answers 404 both for unknown models and on backends without /api/generate
+    let body = response.text().await.unwrap_or_default();
+    Err(format!(
+        "Failed to unload model '{}' (the backend may not support unloading): Status {} {}",
+        model, status, body
+    ))
+}
+
+/// Forces the backend to load `model` into memory by sending it an empty generate
+/// request, so the first real request doesn't pay the load cost. Returns the load
+/// time in milliseconds.
+#[tauri::command]
+pub async fn preload_model(
+    model: String,
+    http_client: State<'_, HttpClientState>,
+    app_handle: AppHandle,
+) -> Result<u64, String> {
+    log::info!("Preloading model '{}'", model);
+
+    let elapsed_ms = timed_generate(&app_handle, &http_client.client(), &model).await?;
+    log::info!("Model '{}' loaded in {}ms", model, elapsed_ms);
+
+    Ok(elapsed_ms)
+}
+
+#[derive(Serialize)]
+pub struct RequestProfile {
+    model: String,
+    // None when the model couldn't be unloaded first
+    cold_ms: Option<u64>,
+    warm_ms: u64,
+    // Model-load overhead, cold_ms - warm_ms
+    load_overhead_ms: Option<i64>,
+    note: Option<String>,
+}
+
+/// Times one request to `model` right after unloading it (cold) and one right
+/// after that (warm). When the backend can't unload, only the warm latency is
+/// reported, with a note explaining why
+#[tauri::command]
+pub async fn profile_request(
+    model: String,
+    http_client: State<'_, HttpClientState>,
+    app_handle: AppHandle,
+) -> Result<RequestProfile, String> {
+    let client = http_client.client();
+    log::info!("Profiling cold and warm requests to model '{}'", model);
+
+    let (cold_ms, note) = match evict_model(&app_handle, &client, &model).await {
+        Ok(()) => (
+            Some(timed_generate(&app_handle, &client, &model).await?),
+            None,
+        ),
+        Err(e) => {
+            log::warn!("{}; reporting warm latency only", e);
+            // Make sure the warm request below really finds the model resident
+            timed_generate(&app_handle, &client, &model).await?;
+            (None, Some(format!("Cold latency unavailable: {}", e)))
+        }
+    };
+    let warm_ms = timed_generate(&app_handle, &client, &model).await?;
+
+    let profile = RequestProfile {
+        load_overhead_ms: cold_ms.map(|cold| cold as i64 - warm_ms as i64),
+        model,
+        cold_ms,
+        warm_ms,
+        note,
+    };
+    log::info!(
+        "Model '{}' profile: cold {:?}ms, warm {}ms",
+        profile.model,
+        profile.cold_ms,
+        profile.warm_ms
+    );
+
+    Ok(profile)
+}
+
+#[derive(Serialize)]
+pub struct OllamaProfileInfo {
+    name: String,
+    url: Option<String>,
+    has_api_key: bool,
+}
+
+/// Lists the saved backend profiles (API keys are not returned)
+#[tauri::command]
+pub async fn list_ollama_profiles(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<Vec<OllamaProfileInfo>, String> {
+    let config = shortcut_state.config.lock().unwrap();
+
+    let mut profiles: Vec<OllamaProfileInfo> = config
+        .ollama_profiles
+        .iter()
+        .map(|(name, profile)| OllamaProfileInfo {
+            name: name.clone(),
+            url: profile.url.clone(),
+            has_api_key: profile.api_key.is_some(),
+        })
+        .collect();
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(profiles)
+}
+
+/// Makes the named profile's URL and API key the active backend settings
+#[tauri::command]
+pub async fn activate_ollama_profile(
+    name: String,
+    settings: State<'_, AppSettings>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let profile = shortcut_state
+        .config
+        .lock()
+        .unwrap()
+        .ollama_profiles
+        .get(&name)
+        .cloned()
+        .ok_or_else(|| format!("No Ollama profile named '{}'", name))?;
+
+    log::info!("Activating Ollama profile '{}' ({:?})", name, profile.url);
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        config.ollama_url = profile.url.clone();
+        config.ollama_api_key = profile.api_key.clone();
+    })?;
+
+    *settings.ollama_url.lock().unwrap() = profile.url;
+    *settings.ollama_api_key.lock().unwrap() = profile.api_key;
+
+    if let Err(e) = app_handle.emit("ollama-profile-changed", &name) {
+        log::warn!("Failed to emit ollama-profile-changed event: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Saves the active URL and API key as a named profile, replacing any existing one
+#[tauri::command]
+pub async fn save_current_as_profile(
+    name: String,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+
+    log::info!("Saving current Ollama settings as profile '{}'", name);
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        let profile = OllamaProfile {
+            url: config.ollama_url.clone(),
+            api_key: config.ollama_api_key.clone(),
+        };
+        config.ollama_profiles.insert(name, profile);
+    })
+}
+
+#[derive(Serialize)]
+pub struct PoolStats {
+    // None means the HTTP client's default (90 seconds)
+    idle_timeout_secs: Option<u64>,
+    // Times the shared client was rebuilt since startup
+    client_rebuilds: u64,
+}
+
+/// Connection pool settings of the shared proxy client. reqwest doesn't expose
+/// live idle/active connection counts, so only the configuration is reported
+#[tauri::command]
+pub async fn get_proxy_pool_stats(
+    http_client: State<'_, HttpClientState>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<PoolStats, String> {
+    Ok(PoolStats {
+        idle_timeout_secs: shortcut_state
+            .config
+            .lock()
+            .unwrap()
+            .proxy_idle_timeout_secs,
+        client_rebuilds: http_client.rebuilds.load(Ordering::SeqCst),
+    })
+}
+
+/// Persists how long idle upstream connections stay pooled (0 closes them as soon
+/// as they go idle) and swaps in a rebuilt shared client. Rebuilding drops every
+/// existing idle connection; in-flight requests finish on the old client
+#[tauri::command]
+pub async fn set_proxy_keepalive(
+    idle_timeout_secs: u64,
+    http_client: State<'_, HttpClientState>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Setting proxy idle timeout to {}s", idle_timeout_secs);
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        config.proxy_idle_timeout_secs = Some(idle_timeout_secs);
+    })?;
+    http_client.replace(crate::build_http_client(
+        &crate::user_agent(&app_handle),
+        Some(idle_timeout_secs),
+    ));
+
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RunningModel {
+    name: String,
+    size: u64,
+    #[serde(default)]
+    size_vram: u64,
+    expires_at: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct RunningModels {
+    // False when the backend has no running-models endpoint (e.g. not Ollama)
+    supported: bool,
+    models: Vec<RunningModel>,
+}
+
+#[derive(Deserialize)]
+struct PsResponse {
+    models: Vec<RunningModel>,
+}
+
+/// Lists the models the backend currently holds in memory (Ollama's /api/ps)
+#[tauri::command]
+pub async fn get_running_models(
+    http_client: State<'_, HttpClientState>,
+    app_handle: AppHandle,
+) -> Result<RunningModels, String> {
+    let client = http_client.client();
+    let target_url = format!("{}/api/ps", crate::ollama_base_url(&app_handle));
+    log::info!("Querying running models at {}", target_url);
+
+    let response = with_api_key(&app_handle, client.get(&target_url))
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to query running models: {}", e))?;
+
+    match response.status() {
+        status if status.is_success() => {
+            let ps = response
+                .json::<PsResponse>()
+                .await
+                .map_err(|e| format!("Failed to parse running models: {}", e))?;
+            Ok(RunningModels {
+                supported: true,
+                models: ps.models,
+            })
+        }
+        StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED => {
+            log::info!("Backend does not support listing running models");
+            Ok(RunningModels {
+                supported: false,
+                models: Vec::new(),
+            })
+        }
+        status => Err(format!("Failed to query running models: Status {}", status)),
+    }
+}
+
+/// Evicts `model` from backend memory by sending it a generate request with keep_alive=0
+#[tauri::command]
+pub async fn unload_model(
+    model: String,
+    http_client: State<'_, HttpClientState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Unloading model '{}'", model);
+
+    evict_model(&app_handle, &http_client.client(), &model).await?;
+    log::info!("Model '{}' unloaded", model);
+
+    Ok(())
+}
+
+/// Headers added to every proxied request that doesn't set them itself
+#[tauri::command]
+pub async fn get_proxy_extra_headers(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<HashMap<String, String>, String> {
+    Ok(shortcut_state
+        .config
+        .lock()
+        .unwrap()
+        .proxy_extra_headers
+        .clone())
+}
+
+/// Sets (or with no value removes) a header added to every proxied request (persisted)
+#[tauri::command]
+pub async fn set_proxy_extra_header(
+    name: String,
+    value: Option<String>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let name = name.trim().to_ascii_lowercase();
+    if let Some(value) = &value {
+        crate::parse_extra_header(&name, value)?;
+    }
+    log::info!(
+        "Setting extra proxy header '{}' (present: {})",
+        name,
+        value.is_some()
+    );
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| match value {
+        Some(value) => {
+            config.proxy_extra_headers.insert(name, value);
+        }
+        None => {
+            config.proxy_extra_headers.remove(&name);
+        }
+    })
+}
+
+#[derive(Serialize)]
+pub struct ProxyResponse {
+    status: u16,
+    headers: HashMap<String, String>,
+    body: String,
+}
+
+/// Sends a raw request through the same path as the HTTP proxy (backend
+/// resolution and API key injection) and returns the full response
+#[tauri::command]
+pub async fn proxy_request(
+    method: String,
+    path: String,
+    body: Option<String>,
+    headers: Option<HashMap<String, String>>,
+    http_client: State<'_, HttpClientState>,
+    app_handle: AppHandle,
+) -> Result<ProxyResponse, String> {
+    let method = Method::from_bytes(method.to_uppercase().as_bytes())
+        .map_err(|_| format!("Invalid HTTP method: {}", method))?;
+    if !path.starts_with('/') {
+        return Err(format!("Path must start with '/': {}", path));
+    }
+    let uri = path
+        .parse::<Uri>()
+        .map_err(|e| format!("Invalid path '{}': {}", path, e))?;
+
+    let mut header_map = HeaderMap::new();
+    for (name, value) in headers.unwrap_or_default() {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| format!("Invalid header name '{}': {}", name, e))?;
+        let value = HeaderValue::from_str(&value)
+            .map_err(|e| format!("Invalid value for header '{}': {}", name, e))?;
+        header_map.insert(name, value);
+    }
+
+    let base_url = crate::ollama_base_url(&app_handle);
+    let api_key = crate::ollama_api_key_for(&app_handle, &base_url);
+    crate::apply_proxy_extra_headers(&app_handle, &mut header_map);
+
+    let client_label = crate::observer_client_label(&header_map);
+    let mut request = Request::new(Bytes::from(body.unwrap_or_default()));
+    *request.method_mut() = method;
+    *request.uri_mut() = uri;
+    *request.headers_mut() = header_map;
+    let response = crate::forward_to_backend(
+        &http_client.client(),
+        &client_label,
+        &base_url,
+        api_key.as_deref(),
+        request,
+    )
+    .await
+    .map_err(|status| format!("Proxy request failed with status {}", status))?;
+
+    let status = response.status().as_u16();
+    let headers = response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|value| (name.to_string(), value.to_string()))
+        })
+        .collect();
+    let body_bytes = response
+        .into_body()
+        .collect()
+        .await
+        .map_err(|e| format!("Failed to read response body: {}", e))?
+        .to_bytes();
+
+    Ok(ProxyResponse {
+        status,
+        headers,
+        body: String::from_utf8_lossy(&body_bytes).into_owned(),
+    })
+}
+
+// How often the status monitor probes the active backend
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(15);
+const STATUS_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// How long a backend the user failed over from manually is tried last
+const DEPRIORITIZE_DURATION: Duration = Duration::from_secs(300);
+
+#[derive(Default)]
+pub struct BackendStatusState {
+    // None until the first probe completes
+    reachable: Mutex<Option<bool>>,
+    // Backends manually failed over from, with when that happened
+    deprioritized: Mutex<HashMap<String, Instant>>,
+}
+
+impl BackendStatusState {
+    fn is_deprioritized(&self, url: &str) -> bool {
+        let mut deprioritized = self.deprioritized.lock().unwrap();
+        deprioritized.retain(|_, since| since.elapsed() < DEPRIORITIZE_DURATION);
+        deprioritized.contains_key(url)
+    }
+}
+
+#[derive(Serialize, Clone)]
+struct BackendStatusEvent {
+    url: String,
+    reachable: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct BackendHealth {
+    url: String,
+    reachable: bool,
+    latency_ms: Option<f64>,
+    // Number of models reported by /api/tags
+    model_count: Option<usize>,
+    // Whether this is the backend the proxy currently uses
+    active: bool,
+    error: Option<String>,
+}
+
+// A backend counts as reachable when /api/tags answers with a success status
+async fn check_backend(app_handle: &AppHandle, base_url: &str) -> BackendHealth {
+    let client = app_handle.state::<HttpClientState>().client();
+    let request = client
+        .get(format!("{}/api/tags", base_url))
+        .timeout(STATUS_PROBE_TIMEOUT);
+
+    let mut health = BackendHealth {
+        url: base_url.to_string(),
+        reachable: false,
+        latency_ms: None,
+        model_count: None,
+        active: crate::ollama_base_url(app_handle) == base_url,
+        error: None,
+    };
+
+    let started = Instant::now();
+    match with_api_key_for(app_handle, base_url, request).send().await {
+        Ok(response) if response.status().is_success() => {
+            health.reachable = true;
+            health.latency_ms = Some(started.elapsed().as_secs_f64() * 1000.0);
+            health.model_count = response
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|tags| tags["models"].as_array().map(|models| models.len()));
+        }
+        Ok(response) => health.error = Some(format!("HTTP {}", response.status())),
+        Err(e) => health.error = Some(e.to_string()),
+    }
+
+    health
+}
+
+async fn probe_backend(app_handle: &AppHandle, base_url: &str) -> bool {
+    check_backend(app_handle, base_url).await.reachable
+}
+
+/// Checks the active backend, the configured URL and every failover backend concurrently
+#[tauri::command]
+pub async fn get_backends_health(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<Vec<BackendHealth>, String> {
+    let mut urls = vec![crate::ollama_base_url(&app_handle)];
+    {
+        let config = shortcut_state.config.lock().unwrap();
+        urls.extend(config.ollama_url.iter().cloned());
+        urls.extend(config.ollama_failover_urls.iter().map(|b| b.url.clone()));
+    }
+    let mut seen = std::collections::HashSet::new();
+    urls.retain(|url| seen.insert(url.clone()));
+
+    let checks = urls.iter().map(|url| check_backend(&app_handle, url));
+    Ok(futures::future::join_all(checks).await)
+}
+
+/// Background task: probes the active backend periodically, emits
+/// `backend-status-changed` on every transition and reacts once per outage
+pub async fn monitor_backend_status(app_handle: AppHandle) {
+    loop {
+        let base_url = crate::ollama_base_url(&app_handle);
+        let reachable = probe_backend(&app_handle, &base_url).await;
+
+        let previous = app_handle
+            .state::<BackendStatusState>()
+            .reachable
+            .lock()
+            .unwrap()
+            .replace(reachable);
+
+        if previous != Some(reachable) {
+            log::info!(
+                "Backend {} is now {}",
+                base_url,
+                if reachable {
+                    "reachable"
+                } else {
+                    "unreachable"
+                }
+            );
+            let event = BackendStatusEvent {
+                url: base_url.clone(),
+                reachable,
+            };
+            if let Err(e) = app_handle.emit("backend-status-changed", &event) {
+                log::warn!("Failed to emit backend-status-changed event: {}", e);
+            }
+
+            if !reachable {
+                on_backend_down(&app_handle, &base_url).await;
+            }
+        }
+
+        tokio::time::sleep(STATUS_POLL_INTERVAL).await;
+    }
+}
+
+// Runs the configured outage reactions; called once when the backend goes down
+async fn on_backend_down(app_handle: &AppHandle, base_url: &str) {
+    let config = app_handle
+        .state::<UnifiedShortcutState>()
+        .config
+        .lock()
+        .unwrap()
+        .clone();
+
+    let mut message = format!("Ollama backend {} is unreachable", base_url);
+
+    if config.backend_down_failover {
+        match try_failover(app_handle, &config.ollama_failover_urls, base_url).await {
+            Some(failover_url) => {
+                message = format!("{}, switched to {}", message, failover_url);
+            }
+            None => log::warn!("No failover backend is reachable"),
+        }
+    }
+
+    if config.backend_down_overlay && config.overlay_enabled {
+        crate::overlay::push_overlay_message(
+            app_handle,
+            crate::overlay::new_overlay_message(message.clone(), Some("system".to_string())),
+        );
+    }
+
+    if config.backend_down_notification && !crate::notifications::is_quiet_now(&config) {
+        if let Err(e) = app_handle
+            .notification()
+            .builder()
+            .title("Observer")
+            .body(&message)
+            .show()
+        {
+            log::error!("Failed to show backend outage notification: {}", e);
+        }
+    }
+}
+
+/// Switches the active backend (in memory only) to the first reachable failover URL
+async fn try_failover(
+    app_handle: &AppHandle,
+    failover_urls: &[BackendEntry],
+    failed_url: &str,
+) -> Option<String> {
+    let status_state = app_handle.state::<BackendStatusState>();
+    let mut candidates: Vec<&String> = failover_urls
+        .iter()
+        .map(|backend| &backend.url)
+        .filter(|url| url.as_str() != failed_url)
+        .collect();
+    // Stable sort, so deprioritized backends keep their relative order at the end
+    candidates.sort_by_key(|url| status_state.is_deprioritized(url));
+
+    for url in candidates {
+        if probe_backend(app_handle, url).await {
+            log::warn!("Failing over from {} to {}", failed_url, url);
+            *app_handle.state::<AppSettings>().ollama_url.lock().unwrap() = Some(url.clone());
+            *app_handle
+                .state::<BackendStatusState>()
+                .reachable
+                .lock()
+                .unwrap() = Some(true);
+
+            if let Err(e) = app_handle.emit("ollama-backend-changed", url) {
+                log::warn!("Failed to emit ollama-backend-changed event: {}", e);
+            }
+            return Some(url.clone());
+        }
+    }
+    None
+}
+
+/// Switches (in memory only) to the next reachable backend after the active one
+/// in the configured URL + failover list, and tries the one being left last for
+/// the next few minutes. Returns the new active URL
+#[tauri::command]
+pub async fn failover_to_next_backend(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    status_state: State<'_, BackendStatusState>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let current_url = crate::ollama_base_url(&app_handle);
+
+    let mut urls: Vec<String> = Vec::new();
+    {
+        let config = shortcut_state.config.lock().unwrap();
+        urls.extend(config.ollama_url.iter().cloned());
+        urls.extend(config.ollama_failover_urls.iter().map(|b| b.url.clone()));
+    }
+    let mut seen = std::collections::HashSet::new();
+    urls.retain(|url| seen.insert(url.clone()));
+
+    // Start right after the active backend and wrap around
+    if let Some(position) = urls.iter().position(|url| *url == current_url) {
+        urls.rotate_left(position + 1);
+    }
+    urls.retain(|url| *url != current_url);
+    if urls.is_empty() {
+        return Err("No other backend is configured".to_string());
+    }
+
+    status_state
+        .deprioritized
+        .lock()
+        .unwrap()
+        .insert(current_url.clone(), Instant::now());
+    urls.sort_by_key(|url| status_state.is_deprioritized(url));
+
+    log::info!("Manual failover requested from {}", current_url);
+    for url in urls {
+        if probe_backend(&app_handle, &url).await {
+            log::info!("Switched backend from {} to {}", current_url, url);
+            *app_handle.state::<AppSettings>().ollama_url.lock().unwrap() = Some(url.clone());
+            *status_state.reachable.lock().unwrap() = Some(true);
+
+            if let Err(e) = app_handle.emit("ollama-backend-changed", &url) {
+                log::warn!("Failed to emit ollama-backend-changed event: {}", e);
+            }
+            return Ok(url);
+        }
+        log::warn!("Skipping unreachable backend {}", url);
+    }
+
+    Err("No other backend is reachable".to_string())
+}
@@ -1,17 +1,45 @@
 // In src-tauri/src/commands.rs
 
+use crate::extract::JsonBody;
+use crate::shortcuts::{self, AppConfig, UnifiedShortcutState};
 use crate::{AppState, CommandMessage, CommandState};
 use axum::{
-    extract::State as AxumState,
-    http::StatusCode,
+    extract::{Query, State as AxumState},
+    http::{HeaderMap, StatusCode},
     response::{sse::Event, Json, Sse},
 };
 use futures::stream::Stream;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tauri::Manager;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
 use tokio_stream::{wrappers::BroadcastStream, StreamExt};
 
+/// Sentinel agent id: a command sent to "*" reaches every subscriber,
+/// including streams filtered to a single agent
+pub const BROADCAST_AGENT_ID: &str = "*";
+
+// Message types agents can branch on; "command" when the caller doesn't say
+pub const DEFAULT_MESSAGE_TYPE: &str = "command";
+const MESSAGE_TYPES: [&str; 3] = ["command", "query", "event"];
+
+/// Resolves an optional message type to a validated one
+pub fn resolve_message_type(message_type: Option<String>) -> Result<String, String> {
+    match message_type {
+        None => Ok(DEFAULT_MESSAGE_TYPE.to_string()),
+        Some(message_type) if MESSAGE_TYPES.contains(&message_type.as_str()) => Ok(message_type),
+        Some(message_type) => Err(format!(
+            "Invalid message type: {} (expected one of {})",
+            message_type,
+            MESSAGE_TYPES.join(", ")
+        )),
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct CommandsResponse {
     commands: HashMap<String, String>,
@@ -19,7 +47,209 @@ pub struct CommandsResponse {
 
 #[derive(Deserialize)]
 pub struct CommandsRequest {
+    #[serde(default)]
     completed: Vec<String>,
+    // Commands to broadcast to SSE subscribers
+    #[serde(default)]
+    commands: Vec<OutgoingCommand>,
+}
+
+#[derive(Deserialize)]
+pub struct OutgoingCommand {
+    agent_id: String,
+    action: String,
+    message_type: Option<String>,
+    payload: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct AgentInfo {
+    agent_id: String,
+    name: Option<String>,
+    shortcut: Option<String>,
+}
+
+// Agent ids that have a shortcut, name, color or icon
+fn known_agent_ids(config: &AppConfig) -> BTreeSet<&String> {
+    config
+        .shortcuts
+        .agent_shortcuts
+        .keys()
+        .chain(config.agent_names.keys())
+        .chain(config.agent_colors.keys())
+        .chain(config.agent_icons.keys())
+        .collect()
+}
+
+/// Every agent id referenced by the config, sorted, with its name and shortcut
+/// binding if any
+pub fn known_agents(config: &AppConfig) -> Vec<AgentInfo> {
+    known_agent_ids(config)
+        .into_iter()
+        .map(|agent_id| AgentInfo {
+            agent_id: agent_id.clone(),
+            name: config.agent_names.get(agent_id).cloned(),
+            shortcut: config
+                .shortcuts
+                .agent_shortcuts
+                .get(agent_id)
+                .filter(|shortcut| !shortcut.key.is_empty())
+                .map(|shortcut| shortcut.key.clone()),
+        })
+        .collect()
+}
+
+#[derive(Serialize, Default)]
+pub struct AgentConfigAudit {
+    // Agents with a name / color / icon but no shortcut binding
+    names_without_shortcut: Vec<String>,
+    colors_without_shortcut: Vec<String>,
+    icons_without_shortcut: Vec<String>,
+    // Agents whose shortcut entry is empty
+    empty_shortcuts: Vec<String>,
+    // Agents bound to a shortcut that nothing else in the config declares
+    // (no name, color or icon), often a typo in the agent id
+    shortcuts_for_unknown_agents: Vec<String>,
+    // Agents with a color or icon but no display name
+    agents_missing_names: Vec<String>,
+    // Allow-listed agents not mentioned anywhere else in the config
+    unknown_allowed_agents: Vec<String>,
+    // Groups of ids that differ only in case or surrounding whitespace
+    near_duplicate_ids: Vec<Vec<String>>,
+}
+
+/// Cross-references the agent-keyed config maps for stale or inconsistent entries
+fn audit_agents(config: &AppConfig) -> AgentConfigAudit {
+    let bound = |agent_id: &String| {
+        config
+            .shortcuts
+            .agent_shortcuts
+            .get(agent_id)
+            .is_some_and(|shortcut| !shortcut.key.is_empty())
+    };
+    let sorted = |ids: Vec<&String>| {
+        let mut ids: Vec<String> = ids.into_iter().cloned().collect();
+        ids.sort();
+        ids
+    };
+
+    let known: BTreeSet<&String> = known_agent_ids(config);
+    let named = |agent_id: &String| {
+        config
+            .agent_names
+            .get(agent_id)
+            .is_some_and(|name| !name.trim().is_empty())
+    };
+    let styled = |agent_id: &String| {
+        config.agent_colors.contains_key(agent_id) || config.agent_icons.contains_key(agent_id)
+    };
+
+    let mut by_canonical: HashMap<String, BTreeSet<&String>> = HashMap::new();
+    for agent_id in &known {
+        by_canonical
+            .entry(agent_id.trim().to_lowercase())
+            .or_default()
+            .insert(agent_id);
+    }
+    let mut near_duplicate_ids: Vec<Vec<String>> = by_canonical
+        .into_values()
+        .filter(|ids| ids.len() > 1)
+        .map(|ids| ids.into_iter().cloned().collect())
+        .collect();
+    near_duplicate_ids.sort();
+
+    AgentConfigAudit {
+        names_without_shortcut: sorted(config.agent_names.keys().filter(|id| !bound(id)).collect()),
+        colors_without_shortcut: sorted(
+            config.agent_colors.keys().filter(|id| !bound(id)).collect(),
+        ),
+        icons_without_shortcut: sorted(config.agent_icons.keys().filter(|id| !bound(id)).collect()),
+        empty_shortcuts: sorted(
+            config
+                .shortcuts
+                .agent_shortcuts
+                .iter()
+                .filter(|(_, shortcut)| shortcut.key.is_empty())
+                .map(|(agent_id, _)| agent_id)
+                .collect(),
+        ),
+        shortcuts_for_unknown_agents: sorted(
+            config
+                .shortcuts
+                .agent_shortcuts
+                .keys()
+                .filter(|id| bound(id) && !named(id) && !styled(id))
+                .collect(),
+        ),
+        agents_missing_names: sorted(
+            known
+                .iter()
+                .copied()
+                .filter(|id| styled(id) && !named(id))
+                .collect(),
+        ),
+        unknown_allowed_agents: sorted(
+            config
+                .click_allowed_agents
+                .iter()
+                .flatten()
+                .filter(|id| !known.contains(*id))
+                .collect(),
+        ),
+        near_duplicate_ids,
+    }
+}
+
+/// Reports orphaned and inconsistent entries across the agent-keyed config maps
+#[tauri::command]
+pub async fn audit_agent_config(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<AgentConfigAudit, String> {
+    let audit = audit_agents(&shortcut_state.config.lock().unwrap());
+    Ok(audit)
+}
+
+/// Sets (or clears, when `name` is None) the display name of an agent
+#[tauri::command]
+pub async fn set_agent_name(
+    agent_id: String,
+    name: Option<String>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Setting name for agent '{}' to {:?}", agent_id, name);
+
+    let name = name.map(|name| name.trim().to_string());
+    if name.as_ref().is_some_and(|name| name.is_empty()) {
+        return Err("Agent name must not be empty".to_string());
+    }
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| match name {
+        Some(name) => {
+            config.agent_names.insert(agent_id, name);
+        }
+        None => {
+            config.agent_names.remove(&agent_id);
+        }
+    })
+}
+
+/// GET /agents - Returns the agents the server knows about
+pub async fn agents_handler(AxumState(state): AxumState<AppState>) -> Json<Vec<AgentInfo>> {
+    log::info!("GET /agents - listing known agents");
+
+    let shortcut_state = state.app_handle.state::<UnifiedShortcutState>();
+    let agents = known_agents(&shortcut_state.config.lock().unwrap());
+
+    Json(agents)
+}
+
+#[tauri::command]
+pub async fn get_agents(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<Vec<AgentInfo>, String> {
+    let agents = known_agents(&shortcut_state.config.lock().unwrap());
+    Ok(agents)
 }
 
 /// GET /commands - Returns pending commands and clears completed ones
@@ -37,9 +267,10 @@ pub async fn get_commands_handler(
 }
 
 /// POST /commands - Marks commands as completed (removes them from pending state)
+/// and broadcasts any `commands` included in the body
 pub async fn post_commands_handler(
     AxumState(state): AxumState<AppState>,
-    Json(payload): Json<CommandsRequest>,
+    JsonBody(payload): JsonBody<CommandsRequest>,
 ) -> StatusCode {
     log::info!(
         "POST /commands - marking {} commands as completed",
@@ -47,56 +278,658 @@ pub async fn post_commands_handler(
     );
 
     let command_state = state.app_handle.state::<CommandState>();
-    let mut commands = command_state.pending_commands.lock().unwrap();
 
-    for agent_id in payload.completed {
-        commands.remove(&agent_id);
-        log::info!("Removed completed command for agent: {}", agent_id);
+    // Validate every message type up front so a bad entry sends nothing
+    let mut outgoing = Vec::with_capacity(payload.commands.len());
+    for command in payload.commands {
+        match resolve_message_type(command.message_type.clone()) {
+            Ok(message_type) => outgoing.push((message_type, command)),
+            Err(e) => {
+                log::warn!("POST /commands rejected: {}", e);
+                return StatusCode::BAD_REQUEST;
+            }
+        }
+    }
+
+    {
+        let mut commands = command_state.pending_commands.lock().unwrap();
+        for agent_id in payload.completed {
+            commands.remove(&agent_id);
+            log::info!("Removed completed command for agent: {}", agent_id);
+        }
+    }
+
+    for (message_type, command) in outgoing {
+        broadcast_command(
+            &state.app_handle,
+            message_type,
+            command.agent_id,
+            command.action,
+            command.payload,
+        );
     }
 
     StatusCode::OK
 }
 
-/// SSE endpoint for real-time command streaming
+#[derive(Deserialize)]
+pub struct CommandsStreamQuery {
+    // When set, only commands for this agent (or the broadcast sentinel) are streamed
+    agent_id: Option<String>,
+}
+
+// Sent by reconnecting EventSource clients with the id of the last event they got
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+// Whether a stream filtered to `agent_filter` should receive the command
+fn is_for_agent(command_msg: &CommandMessage, agent_filter: Option<&str>) -> bool {
+    match agent_filter {
+        Some(agent_id) => {
+            command_msg.agent_id == agent_id || command_msg.agent_id == BROADCAST_AGENT_ID
+        }
+        None => true,
+    }
+}
+
+/// SSE endpoint for real-time command streaming. Events carry increasing ids;
+/// a client reconnecting with `Last-Event-ID` first gets the commands it missed
+/// that are still in the history
 pub async fn commands_stream_handler(
     AxumState(state): AxumState<AppState>,
+    Query(query): Query<CommandsStreamQuery>,
+    headers: HeaderMap,
 ) -> Sse<impl Stream<Item = Result<Event, Box<dyn std::error::Error + Send + Sync>>>> {
-    log::info!("New SSE client connected to commands stream");
+    let last_event_id = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    log::info!(
+        "New SSE client connected to commands stream (agent filter: {:?}, last event id: {:?})",
+        query.agent_id,
+        last_event_id
+    );
 
     let command_state = state.app_handle.state::<CommandState>();
+    // Subscribe before reading the history so nothing sent in between is lost
     let rx = command_state.command_broadcaster.subscribe();
+    let agent_filter = query.agent_id;
+    let missed: Vec<(u64, CommandMessage)> = match last_event_id {
+        Some(last_event_id) => command_state
+            .history
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|record| {
+                let event_id = record.event_id.filter(|id| *id > last_event_id)?;
+                is_for_agent(&record.command, agent_filter.as_deref())
+                    .then(|| (event_id, record.command.clone()))
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    // Commands sent after subscribing can be both in the history and the channel
+    let replayed_up_to = missed.last().map(|(event_id, _)| *event_id).unwrap_or(0);
+    if !missed.is_empty() {
+        log::info!(
+            "Replaying {} missed commands to reconnected SSE client",
+            missed.len()
+        );
+    }
+    let max_lifetime = state
+        .app_handle
+        .state::<UnifiedShortcutState>()
+        .config
+        .lock()
+        .unwrap()
+        .sse_max_lifetime_secs
+        .map(Duration::from_secs);
 
-    let stream = BroadcastStream::new(rx).map(|result| match result {
-        Ok(command_msg) => {
-            log::debug!("Broadcasting command via SSE: {:?}", command_msg);
-            match serde_json::to_string(&command_msg) {
-                Ok(json) => Ok(Event::default().data(json)),
-                Err(e) => {
-                    log::error!("Failed to serialize command message: {}", e);
-                    Err(Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+    let live = BroadcastStream::new(rx).filter(move |result| match result {
+        Ok((event_id, command_msg)) => {
+            *event_id > replayed_up_to && is_for_agent(command_msg, agent_filter.as_deref())
+        }
+        Err(_) => true,
+    });
+    let stream = tokio_stream::iter(missed.into_iter().map(Ok))
+        .chain(live)
+        .map(|result| match result {
+            Ok((event_id, command_msg)) => {
+                log::debug!("Broadcasting command via SSE: {:?}", command_msg);
+                match serde_json::to_string(&command_msg) {
+                    Ok(json) => Ok(Event::default().id(event_id.to_string()).data(json)),
+                    Err(e) => {
+                        log::error!("Failed to serialize command message: {}", e);
+                        Err(Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+                    }
                 }
             }
+            Err(e) => {
+                log::warn!("SSE broadcast error: {}", e);
+                Err(Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+            }
+        });
+
+    // With a max lifetime the stream ends after a final reconnect event; the
+    // receiver is dropped with it, which removes the client from the subscriber count
+    let deadline = async move {
+        match max_lifetime {
+            Some(lifetime) => tokio::time::sleep(lifetime).await,
+            None => std::future::pending::<()>().await,
         }
+    };
+    let reconnect = tokio_stream::iter(max_lifetime).map(|_| {
+        log::info!("SSE client reached its max lifetime, asking it to reconnect");
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(
+            Event::default().data(r#"{"type":"reconnect"}"#),
+        )
+    });
+
+    Sse::new(futures::StreamExt::take_until(stream, deadline).chain(reconnect))
+}
+
+// Number of broadcasts kept for get_command_history
+pub const MAX_COMMAND_HISTORY: usize = 200;
+
+// Commands held while paused; past this the oldest held command is dropped
+pub const MAX_HELD_COMMANDS: usize = 100;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommandRecord {
+    // Empty for records imported from snapshots taken before ids existed
+    #[serde(default)]
+    pub id: String,
+    // Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    pub command: CommandMessage,
+    // SSE subscribers reached; 0 while held
+    pub delivered: usize,
+    // Whether the command was held while the broadcaster was paused
+    pub held: bool,
+    // Id of the SSE event that carried the command; None while held or once dropped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<u64>,
+    // Id of the record this command re-sent, for replays
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_of: Option<String>,
+}
+
+/// A command held while the broadcaster is paused
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HeldCommand {
+    // History record made when the command was held, updated when it is sent.
+    // Empty for snapshots taken before held commands kept their record
+    #[serde(default)]
+    pub record_id: String,
+    // Whether the command also goes to in-process agents once sent (dispatch_command)
+    #[serde(default)]
+    pub to_frontend: bool,
+    #[serde(flatten)]
+    pub command: CommandMessage,
+}
+
+// The audit file is rotated to "<file>.1" once it grows past this size
+const MAX_COMMAND_AUDIT_BYTES: u64 = 5 * 1024 * 1024;
+
+// Relative audit paths are resolved against the app data directory
+fn command_audit_path(app_handle: &AppHandle, file: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(file);
+    if path.is_absolute() {
+        return Some(path);
+    }
+    match app_handle.path().app_data_dir() {
+        Ok(dir) => Some(dir.join(path)),
         Err(e) => {
-            log::warn!("SSE broadcast error: {}", e);
-            Err(Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+            log::warn!("Failed to resolve command audit path: {}", e);
+            None
         }
+    }
+}
+
+fn write_command_audit_line(path: &Path, line: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if std::fs::metadata(path).is_ok_and(|m| m.len() >= MAX_COMMAND_AUDIT_BYTES) {
+        let mut rotated = path.as_os_str().to_owned();
+        rotated.push(".1");
+        std::fs::rename(path, rotated)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// Appends one NDJSON line per history record to `command_audit_file`, if
+/// configured. Held commands are written once, when they are sent or dropped
+fn audit_command(app_handle: &AppHandle, record: &CommandRecord) {
+    let (file, redact) = {
+        let shortcut_state = app_handle.state::<UnifiedShortcutState>();
+        let config = shortcut_state.config.lock().unwrap();
+        match &config.command_audit_file {
+            Some(file) => (file.clone(), config.command_audit_redact_payloads),
+            None => return,
+        }
+    };
+    let Some(path) = command_audit_path(app_handle, &file) else {
+        return;
+    };
+
+    let command_msg = &record.command;
+    let payload = match &command_msg.payload {
+        Some(_) if redact => Some(serde_json::Value::String(
+            crate::config_diff::REDACTED.to_string(),
+        )),
+        payload => payload.clone(),
+    };
+    let line = serde_json::json!({
+        "id": record.id,
+        "timestamp": record.timestamp,
+        "agent_id": command_msg.agent_id,
+        "action": command_msg.action,
+        "type": command_msg.message_type,
+        "payload": payload,
+        "delivered": record.delivered,
+        "held": record.held,
+        "event_id": record.event_id,
+        "replay_of": record.replay_of,
     });
 
-    Sse::new(stream)
+    if let Err(e) = write_command_audit_line(&path, &line.to_string()) {
+        log::warn!("Failed to write command audit to {}: {}", path.display(), e);
+    }
+}
+
+fn new_record(
+    command: CommandMessage,
+    delivered: usize,
+    held: bool,
+    event_id: Option<u64>,
+    replay_of: Option<String>,
+) -> CommandRecord {
+    CommandRecord {
+        id: uuid::Uuid::new_v4().to_string(),
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+        command,
+        delivered,
+        held,
+        event_id,
+        replay_of,
+    }
+}
+
+// Appends to the history, dropping the oldest record once it is full
+fn push_history(history: &mut VecDeque<CommandRecord>, record: CommandRecord) {
+    if history.len() >= MAX_COMMAND_HISTORY {
+        history.pop_front();
+    }
+    history.push_back(record);
+}
+
+// Sends to SSE subscribers, and with `to_frontend` to in-process agents via
+// the `agent-command` event, returning how many SSE subscribers received the
+// command. A flushed command passes the record made when it was held, which is
+// updated and moved to the end of the history instead of adding a second record
+fn send_command(
+    app_handle: &AppHandle,
+    command_msg: CommandMessage,
+    replay_of: Option<String>,
+    held_record: Option<&str>,
+    to_frontend: bool,
+) -> usize {
+    let command_state = app_handle.state::<CommandState>();
+    let record = {
+        // Held until the command is in the history, so a reconnecting client
+        // that subscribed meanwhile gets it either live or replayed. The audit
+        // file is written after it is released so slow disk I/O doesn't hold up
+        // other broadcasts
+        let mut last_event_id = command_state.last_event_id.lock().unwrap();
+        *last_event_id += 1;
+        let event_id = *last_event_id;
+        let record_msg = command_msg.clone();
+        let delivered = match command_state
+            .command_broadcaster
+            .send((event_id, command_msg))
+        {
+            Ok(receivers) => receivers,
+            Err(e) => {
+                log::warn!("Failed to broadcast command (no active SSE clients): {}", e);
+                0
+            }
+        };
+
+        let mut history = command_state.history.lock().unwrap();
+        let held = held_record
+            .and_then(|id| {
+                history
+                    .iter()
+                    .position(|record| !record.id.is_empty() && record.id == id)
+            })
+            .and_then(|index| history.remove(index));
+        let record = match held {
+            Some(record) => CommandRecord {
+                delivered,
+                event_id: Some(event_id),
+                ..record
+            },
+            None => new_record(
+                record_msg,
+                delivered,
+                held_record.is_some(),
+                Some(event_id),
+                replay_of,
+            ),
+        };
+        push_history(&mut history, record.clone());
+        record
+    };
+    audit_command(app_handle, &record);
+    if to_frontend {
+        if let Err(e) = app_handle.emit("agent-command", &record.command) {
+            log::warn!("Failed to emit agent-command event: {}", e);
+        }
+    }
+    record.delivered
+}
+
+// Writes the audit line for a held command that is dropped without being sent
+fn audit_dropped(app_handle: &AppHandle, held_command: HeldCommand) {
+    let record = app_handle
+        .state::<CommandState>()
+        .history
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|record| !record.id.is_empty() && record.id == held_command.record_id)
+        .cloned();
+    let record = record.unwrap_or_else(|| CommandRecord {
+        id: held_command.record_id,
+        ..new_record(held_command.command, 0, true, None, None)
+    });
+    audit_command(app_handle, &record);
 }
 
 /// Internal function to broadcast a command via SSE (called by shortcut system)
-pub fn broadcast_command(command_state: &CommandState, agent_id: String, action: String) {
-    log::info!("Broadcasting {} command for agent '{}'", action, agent_id);
+pub fn broadcast_command(
+    app_handle: &AppHandle,
+    message_type: String,
+    agent_id: String,
+    action: String,
+    payload: Option<serde_json::Value>,
+) {
+    let command_msg = CommandMessage {
+        message_type,
+        agent_id,
+        action,
+        payload,
+        replay_of: None,
+    };
+    send_or_hold(app_handle, command_msg, false);
+}
+
+// Sends the command now, or holds it while the broadcaster is paused
+fn send_or_hold(app_handle: &AppHandle, command_msg: CommandMessage, to_frontend: bool) {
+    log::info!(
+        "Broadcasting {} {} for agent '{}'",
+        command_msg.action,
+        command_msg.message_type,
+        command_msg.agent_id
+    );
+
+    // The flag is checked under the held_commands lock so resume_commands
+    // can't drain the list between the check and the push
+    let command_state = app_handle.state::<CommandState>();
+    let (held, dropped) = {
+        let mut held_commands = command_state.held_commands.lock().unwrap();
+        if command_state.broadcaster_paused.load(Ordering::SeqCst) {
+            let record = new_record(command_msg.clone(), 0, true, None, None);
+            held_commands.push_back(HeldCommand {
+                record_id: record.id.clone(),
+                to_frontend,
+                command: command_msg.clone(),
+            });
+            // Recorded before the lock is released so a flush finds the record
+            push_history(&mut command_state.history.lock().unwrap(), record);
+            let dropped = if held_commands.len() > MAX_HELD_COMMANDS {
+                held_commands.pop_front()
+            } else {
+                None
+            };
+            (true, dropped)
+        } else {
+            (false, None)
+        }
+    };
 
+    if let Some(dropped) = dropped {
+        log::warn!(
+            "More than {} held commands, dropping the oldest ({} for agent '{}')",
+            MAX_HELD_COMMANDS,
+            dropped.command.action,
+            dropped.command.agent_id
+        );
+        audit_dropped(app_handle, dropped);
+    }
+    if held {
+        log::info!("Command broadcaster paused, holding command");
+    } else {
+        send_command(app_handle, command_msg, None, None, to_frontend);
+    }
+}
+
+/// Sends a command to external SSE subscribers and, via the `agent-command` event,
+/// to agents running inside the frontend. While the broadcaster is paused both
+/// wait for `resume_commands`
+#[tauri::command]
+pub async fn dispatch_command(
+    agent_id: String,
+    action: String,
+    payload: Option<serde_json::Value>,
+    message_type: Option<String>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
     let command_msg = CommandMessage {
-        message_type: "command".to_string(),
+        message_type: resolve_message_type(message_type)?,
         agent_id,
         action,
+        payload,
+        replay_of: None,
     };
+    send_or_hold(&app_handle, command_msg, true);
+
+    Ok(())
+}
+
+/// Recent broadcasts, newest first (at most `limit` entries)
+#[tauri::command]
+pub async fn get_command_history(
+    limit: Option<usize>,
+    command_state: State<'_, CommandState>,
+) -> Result<Vec<CommandRecord>, String> {
+    let history = command_state.history.lock().unwrap();
+    Ok(history
+        .iter()
+        .rev()
+        .take(limit.unwrap_or(MAX_COMMAND_HISTORY))
+        .cloned()
+        .collect())
+}
+
+/// Re-sends a command from the history to SSE subscribers and frontend agents,
+/// marked with `replayOf`. The replay gets its own history record (new id and
+/// timestamp, `replay_of` set) and SSE event id. Returns the number of SSE
+/// subscribers reached
+#[tauri::command]
+pub async fn replay_command(
+    command_id: String,
+    command_state: State<'_, CommandState>,
+    app_handle: AppHandle,
+) -> Result<usize, String> {
+    let command_msg = command_state
+        .history
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|record| !record.id.is_empty() && record.id == command_id)
+        .map(|record| CommandMessage {
+            replay_of: Some(command_id.clone()),
+            ..record.command.clone()
+        })
+        .ok_or_else(|| format!("No command with id {} in history", command_id))?;
+    if command_state.broadcaster_paused.load(Ordering::SeqCst) {
+        return Err("Command broadcaster is paused".to_string());
+    }
 
-    if let Err(e) = command_state.command_broadcaster.send(command_msg) {
-        log::warn!("Failed to broadcast command (no active SSE clients): {}", e);
+    log::info!(
+        "Replaying command {}: {} {} for agent '{}'",
+        command_id,
+        command_msg.action,
+        command_msg.message_type,
+        command_msg.agent_id
+    );
+
+    let delivered = send_command(&app_handle, command_msg, Some(command_id), None, true);
+
+    Ok(delivered)
+}
+
+#[derive(Serialize)]
+pub struct AgentActivity {
+    agent_id: String,
+    // Milliseconds since the Unix epoch; None when nothing is recorded
+    last_command_at: Option<u64>,
+    // Latest overlay message tagged with the agent
+    last_message_at: Option<u64>,
+    // Since the most recent of the two; None for agents with no activity
+    idle_for_secs: Option<u64>,
+}
+
+/// Last command sent to and last overlay message from each agent, most idle
+/// first. Covers registered agents plus any agent seen in the command history
+/// or the overlay; broadcasts don't count as activity for individual agents
+#[tauri::command]
+pub async fn get_agent_activity(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    command_state: State<'_, CommandState>,
+    overlay_state: State<'_, crate::OverlayState>,
+) -> Result<Vec<AgentActivity>, String> {
+    let mut last_commands: HashMap<String, Option<u64>> = {
+        let config = shortcut_state.config.lock().unwrap();
+        known_agent_ids(&config)
+            .into_iter()
+            .map(|agent_id| (agent_id.clone(), None))
+            .collect()
+    };
+    for record in command_state.history.lock().unwrap().iter() {
+        if record.command.agent_id != BROADCAST_AGENT_ID {
+            let last = last_commands
+                .entry(record.command.agent_id.clone())
+                .or_default();
+            *last = (*last).max(Some(record.timestamp));
+        }
     }
+
+    let mut last_messages: HashMap<String, u64> = HashMap::new();
+    for message in overlay_state.messages.lock().unwrap().iter() {
+        if let Some(agent_id) = &message.agent_id {
+            // Overlay timestamps are in seconds
+            let last = last_messages.entry(agent_id.clone()).or_default();
+            *last = (*last).max(message.timestamp * 1000);
+        }
+    }
+
+    let agent_ids: BTreeSet<String> = last_commands
+        .keys()
+        .chain(last_messages.keys())
+        .cloned()
+        .collect();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let mut activity: Vec<AgentActivity> = agent_ids
+        .into_iter()
+        .map(|agent_id| {
+            let last_command_at = last_commands.get(&agent_id).copied().flatten();
+            let last_message_at = last_messages.get(&agent_id).copied();
+            let idle_for_secs = last_command_at
+                .max(last_message_at)
+                .map(|last| now.saturating_sub(last) / 1000);
+            AgentActivity {
+                agent_id,
+                last_command_at,
+                last_message_at,
+                idle_for_secs,
+            }
+        })
+        .collect();
+    // Agents with no activity at all sort first, then the longest idle
+    activity.sort_by_key(|a| std::cmp::Reverse(a.idle_for_secs.unwrap_or(u64::MAX)));
+
+    Ok(activity)
+}
+
+fn emit_paused_changed(app_handle: &AppHandle, paused: bool) {
+    if let Err(e) = app_handle.emit("commands-paused-changed", paused) {
+        log::warn!("Failed to emit commands-paused-changed event: {}", e);
+    }
+}
+
+/// Pauses the command broadcaster; commands are held until `resume_commands`
+#[tauri::command]
+pub async fn pause_commands(
+    command_state: State<'_, CommandState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Pausing command broadcaster");
+    command_state
+        .broadcaster_paused
+        .store(true, Ordering::SeqCst);
+    emit_paused_changed(&app_handle, true);
+    Ok(())
+}
+
+/// Resumes the command broadcaster, flushing held commands unless `drop_held` is set
+#[tauri::command]
+pub async fn resume_commands(
+    drop_held: Option<bool>,
+    command_state: State<'_, CommandState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    // Unpaused under the lock so no broadcast can be held after the drain
+    let held: Vec<HeldCommand> = {
+        let mut held_commands = command_state.held_commands.lock().unwrap();
+        command_state
+            .broadcaster_paused
+            .store(false, Ordering::SeqCst);
+        held_commands.drain(..).collect()
+    };
+
+    if drop_held.unwrap_or(false) {
+        log::info!(
+            "Resuming command broadcaster, dropping {} held commands",
+            held.len()
+        );
+        for held_command in held {
+            audit_dropped(&app_handle, held_command);
+        }
+    } else {
+        log::info!(
+            "Resuming command broadcaster, flushing {} held commands",
+            held.len()
+        );
+        for held_command in held {
+            send_command(
+                &app_handle,
+                held_command.command,
+                None,
+                Some(&held_command.record_id),
+                held_command.to_frontend,
+            );
+        }
+    }
+
+    emit_paused_changed(&app_handle, false);
+    Ok(())
 }
@@ -0,0 +1,139 @@
+// In src-tauri/src/config_diff.rs
+
+use crate::shortcuts::{AppConfig, UnifiedShortcutState};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tauri::State;
+
+// Values of fields whose name contains this are never returned
+const SECRET_FIELD_MARKER: &str = "api_key";
+// Maps whose values are all treated as secrets; extra proxy headers routinely
+// carry tokens and cookies under arbitrary names
+const SECRET_MAPS: [&str; 1] = ["proxy_extra_headers"];
+pub const REDACTED: &str = "<redacted>";
+
+#[derive(Serialize, Debug)]
+pub struct FieldChange {
+    // Dotted path of the field, e.g. "shortcuts.agent_shortcuts.my-agent"
+    path: String,
+    old: Option<Value>,
+    new: Option<Value>,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct ConfigDiff {
+    added: Vec<FieldChange>,
+    removed: Vec<FieldChange>,
+    changed: Vec<FieldChange>,
+}
+
+// Flattens objects into dotted paths; arrays and scalars are compared whole,
+// and nulls (unset options) are treated as absent
+fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&path, value, out);
+            }
+        }
+        Value::Null => {}
+        _ => {
+            out.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+fn redact(path: &str, value: Value) -> Value {
+    let field = path.rsplit('.').next().unwrap_or(path);
+    let in_secret_map = SECRET_MAPS.iter().any(|map| {
+        path.strip_prefix(map)
+            .is_some_and(|rest| rest.starts_with('.'))
+    });
+    if field.contains(SECRET_FIELD_MARKER) || in_secret_map {
+        return Value::String(REDACTED.to_string());
+    }
+    match value {
+        // Arrays are compared whole, so secrets inside their objects
+        // (e.g. failover backend keys) are redacted in place
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| match item {
+                    Value::Object(map) => Value::Object(
+                        map.into_iter()
+                            .map(|(key, value)| {
+                                let value = redact(&key, value);
+                                (key, value)
+                            })
+                            .collect(),
+                    ),
+                    other => other,
+                })
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+/// Field-level differences between two configs, with secrets redacted
+pub fn diff(current: &AppConfig, incoming: &AppConfig) -> Result<ConfigDiff, String> {
+    let mut current_fields = BTreeMap::new();
+    let mut incoming_fields = BTreeMap::new();
+    flatten(
+        "",
+        &serde_json::to_value(current).map_err(|e| e.to_string())?,
+        &mut current_fields,
+    );
+    flatten(
+        "",
+        &serde_json::to_value(incoming).map_err(|e| e.to_string())?,
+        &mut incoming_fields,
+    );
+
+    let mut result = ConfigDiff::default();
+
+    for (path, old) in &current_fields {
+        match incoming_fields.get(path) {
+            None => result.removed.push(FieldChange {
+                path: path.clone(),
+                old: Some(redact(path, old.clone())),
+                new: None,
+            }),
+            Some(new) if new != old => result.changed.push(FieldChange {
+                path: path.clone(),
+                old: Some(redact(path, old.clone())),
+                new: Some(redact(path, new.clone())),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    for (path, new) in incoming_fields {
+        if !current_fields.contains_key(&path) {
+            let new = redact(&path, new);
+            result.added.push(FieldChange {
+                path,
+                old: None,
+                new: Some(new),
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+/// Compares `incoming` against the current config so the UI can confirm an import
+#[tauri::command]
+pub async fn diff_config(
+    incoming: AppConfig,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<ConfigDiff, String> {
+    let current = shortcut_state.config.lock().unwrap().clone();
+    diff(&current, &incoming)
+}
@@ -1,39 +1,878 @@
 // In src-tauri/src/controls.rs
 
+use crate::extract::JsonBody;
+use crate::shortcuts::{self, UnifiedShortcutState};
 use crate::AppState;
-use axum::{extract::State as AxumState, http::StatusCode};
+use axum::{
+    extract::{Query, State as AxumState},
+    http::{header::ORIGIN, HeaderMap, StatusCode},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
 
 // Desktop-only implementation using Enigo
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
-use enigo::{Button, Enigo, Mouse, Settings};
+use enigo::{Axis, Button, Coordinate, Direction, Enigo, Key, Keyboard, Mouse, Settings};
 
-/// Handler for /click endpoint
-/// Triggers a mouse click at the current cursor position (desktop only)
-#[cfg(not(any(target_os = "android", target_os = "ios")))]
-pub async fn click_handler(AxumState(_state): AxumState<AppState>) -> StatusCode {
-    log::info!("Received click request");
+// Log target for the audit trail of every synthesized input
+const AUDIT_TARGET: &str = "controls_audit";
 
-    match Enigo::new(&Settings::default()) {
-        Ok(mut enigo) => match enigo.button(Button::Left, enigo::Direction::Click) {
-            Ok(_) => {
-                log::info!("Mouse click executed successfully");
-                StatusCode::OK
-            }
-            Err(e) => {
-                log::error!("Failed to execute mouse click: {}", e);
-                StatusCode::INTERNAL_SERVER_ERROR
+// Long text is typed in chunks with a pause in between so the target app
+// doesn't drop characters
+const TYPE_CHUNK_CHARS: usize = 32;
+const TYPE_CHUNK_DELAY: Duration = Duration::from_millis(20);
+const MAX_TYPE_TEXT_CHARS: usize = 10_000;
+
+// Scroll deltas are wheel notches; anything larger is clamped to this
+const MAX_SCROLL_DELTA: i32 = 50;
+
+#[derive(Deserialize)]
+pub struct ControlsQuery {
+    agent_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct KeyPayload {
+    key: String,
+    #[serde(default)]
+    modifiers: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+pub struct TypePayload {
+    text: String,
+}
+
+#[derive(Deserialize)]
+pub struct ScrollPayload {
+    x: i32,
+    y: i32,
+    #[serde(default)]
+    delta_x: i32,
+    #[serde(default)]
+    delta_y: i32,
+}
+
+#[derive(Debug)]
+enum ControlsError {
+    Disabled,
+    NotAllowed,
+    Invalid(String),
+    // Only constructed by the mobile stubs
+    #[cfg_attr(desktop, allow(dead_code))]
+    Unsupported,
+    Failed(String),
+}
+
+impl ControlsError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ControlsError::Disabled | ControlsError::NotAllowed => StatusCode::FORBIDDEN,
+            ControlsError::Invalid(_) => StatusCode::BAD_REQUEST,
+            ControlsError::Unsupported => StatusCode::NOT_IMPLEMENTED,
+            ControlsError::Failed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl fmt::Display for ControlsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControlsError::Disabled => write!(f, "Controls are disabled"),
+            ControlsError::NotAllowed => write!(f, "Agent is not in the controls allow-list"),
+            ControlsError::Invalid(e) => write!(f, "{}", e),
+            ControlsError::Unsupported => write!(f, "Input control not available on mobile"),
+            ControlsError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Time of the last synthesized input event, shared by every request so the
+/// configured delay also spaces out rapid consecutive requests
+#[derive(Default)]
+pub struct ControlsState {
+    last_event: Arc<Mutex<Option<Instant>>>,
+}
+
+/// Spaces synthesized events by `input_event_delay_ms`
+struct Pacer {
+    delay: Duration,
+    last_event: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Pacer {
+    fn new(app_handle: &AppHandle) -> Self {
+        let shortcut_state = app_handle.state::<UnifiedShortcutState>();
+        let delay_ms = shortcut_state
+            .config
+            .lock()
+            .unwrap()
+            .input_event_delay_ms
+            .unwrap_or(0);
+        Self {
+            delay: Duration::from_millis(delay_ms),
+            last_event: app_handle.state::<ControlsState>().last_event.clone(),
+        }
+    }
+
+    /// Blocks until the delay has passed since the previous event
+    fn wait(&self) {
+        if self.delay.is_zero() {
+            return;
+        }
+        let mut last_event = self.last_event.lock().unwrap();
+        if let Some(elapsed) = last_event.map(|at| at.elapsed()) {
+            if elapsed < self.delay {
+                std::thread::sleep(self.delay - elapsed);
             }
+        }
+        *last_event = Some(Instant::now());
+    }
+}
+
+/// Checks the requesting agent against the configured allow-list, if any. The
+/// agent id is whatever the caller put in the query, so this only narrows which
+/// of the app's own agents may drive input; access itself comes from the
+/// controls_enabled opt-in and the app-origin restriction on these routes
+fn is_agent_allowed(state: &AppState, agent_id: Option<&str>) -> bool {
+    let shortcut_state = state.app_handle.state::<UnifiedShortcutState>();
+    let config = shortcut_state.config.lock().unwrap();
+
+    match &config.click_allowed_agents {
+        Some(allowed) => match agent_id {
+            Some(agent_id) => allowed.iter().any(|allowed_id| allowed_id == agent_id),
+            None => false,
         },
-        Err(e) => {
-            log::error!("Failed to initialize Enigo: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
+        None => true,
+    }
+}
+
+fn ensure_controls_enabled(app_handle: &AppHandle) -> Result<(), ControlsError> {
+    let shortcut_state = app_handle.state::<UnifiedShortcutState>();
+    if shortcut_state.config.lock().unwrap().controls_enabled {
+        Ok(())
+    } else {
+        Err(ControlsError::Disabled)
+    }
+}
+
+/// Gate shared by the HTTP endpoints: the opt-in first, then the allow-list
+fn check_agent_access(state: &AppState, agent_id: Option<&str>) -> Result<(), ControlsError> {
+    ensure_controls_enabled(&state.app_handle)?;
+    if !is_agent_allowed(state, agent_id) {
+        return Err(ControlsError::NotAllowed);
+    }
+    Ok(())
+}
+
+/// Who asked for an input over HTTP, for the audit log. The agent id is only
+/// declared by the caller, so the request's origin is recorded next to it
+fn http_source(agent_id: Option<&str>, headers: &HeaderMap) -> String {
+    let origin = headers
+        .get(ORIGIN)
+        .and_then(|origin| origin.to_str().ok())
+        .unwrap_or("no origin");
+    format!(
+        "agent {} (declared) from {}",
+        agent_id.unwrap_or("<none>"),
+        origin
+    )
+}
+
+/// Records a synthesized input (or a refused attempt) in the audit log
+fn audit(source: &str, action: &str, result: &Result<(), ControlsError>) {
+    match result {
+        Ok(()) => log::info!(target: AUDIT_TARGET, "{} by {}: ok", action, source),
+        Err(e) => log::warn!(target: AUDIT_TARGET, "{} by {}: {}", action, source, e),
+    }
+}
+
+/// Validates a key and its modifiers with the shortcut syntax, e.g. ("Enter", ["Ctrl"]),
+/// and that Enigo can synthesize each of them on this platform
+fn validate_key_press(key: &str, modifiers: &[String]) -> Result<(), ControlsError> {
+    let combo = modifiers
+        .iter()
+        .map(String::as_str)
+        .chain(std::iter::once(key))
+        .collect::<Vec<_>>()
+        .join("+");
+    shortcuts::normalize_shortcut(&combo).map_err(ControlsError::Invalid)?;
+
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    {
+        let unsupported = |name: &str| ControlsError::Invalid(format!("Unsupported key: {}", name));
+        if enigo_key(key).is_none() {
+            return Err(unsupported(key));
+        }
+        if let Some(modifier) = modifiers.iter().find(|m| enigo_modifier(m).is_none()) {
+            return Err(unsupported(modifier));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn new_enigo() -> Result<Enigo, ControlsError> {
+    Enigo::new(&Settings::default())
+        .map_err(|e| ControlsError::Failed(format!("Failed to initialize Enigo: {}", e)))
+}
+
+/// Maps a key name accepted by the shortcut parser to an Enigo key
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn enigo_key(name: &str) -> Option<Key> {
+    let key = match name {
+        "F1" => Key::F1,
+        "F2" => Key::F2,
+        "F3" => Key::F3,
+        "F4" => Key::F4,
+        "F5" => Key::F5,
+        "F6" => Key::F6,
+        "F7" => Key::F7,
+        "F8" => Key::F8,
+        "F9" => Key::F9,
+        "F10" => Key::F10,
+        "F11" => Key::F11,
+        "F12" => Key::F12,
+        "F13" => Key::F13,
+        "F14" => Key::F14,
+        "F15" => Key::F15,
+        "F16" => Key::F16,
+        "F17" => Key::F17,
+        "F18" => Key::F18,
+        "F19" => Key::F19,
+        "F20" => Key::F20,
+        // macOS has no F21-F24
+        #[cfg(not(target_os = "macos"))]
+        "F21" => Key::F21,
+        #[cfg(not(target_os = "macos"))]
+        "F22" => Key::F22,
+        #[cfg(not(target_os = "macos"))]
+        "F23" => Key::F23,
+        #[cfg(not(target_os = "macos"))]
+        "F24" => Key::F24,
+
+        "Numpad0" => Key::Numpad0,
+        "Numpad1" => Key::Numpad1,
+        "Numpad2" => Key::Numpad2,
+        "Numpad3" => Key::Numpad3,
+        "Numpad4" => Key::Numpad4,
+        "Numpad5" => Key::Numpad5,
+        "Numpad6" => Key::Numpad6,
+        "Numpad7" => Key::Numpad7,
+        "Numpad8" => Key::Numpad8,
+        "Numpad9" => Key::Numpad9,
+        "NumpadAdd" => Key::Add,
+        "NumpadSubtract" => Key::Subtract,
+        "NumpadMultiply" => Key::Multiply,
+        "NumpadDivide" => Key::Divide,
+        "NumpadDecimal" => Key::Decimal,
+        // Enigo has no separate keypad Enter
+        "NumpadEnter" => Key::Return,
+
+        "ArrowUp" => Key::UpArrow,
+        "ArrowDown" => Key::DownArrow,
+        "ArrowLeft" => Key::LeftArrow,
+        "ArrowRight" => Key::RightArrow,
+
+        "Space" => Key::Space,
+        "Enter" => Key::Return,
+        "Tab" => Key::Tab,
+        "Escape" => Key::Escape,
+        "Backspace" => Key::Backspace,
+        "Delete" => Key::Delete,
+        "Home" => Key::Home,
+        "End" => Key::End,
+        "PageUp" => Key::PageUp,
+        "PageDown" => Key::PageDown,
+
+        // Punctuation is typed by character, like letters and digits
+        "Comma" | "," => Key::Unicode(','),
+        "Period" | "." => Key::Unicode('.'),
+        "Slash" | "/" => Key::Unicode('/'),
+        "Backslash" | "\\" => Key::Unicode('\\'),
+        "Semicolon" | ";" => Key::Unicode(';'),
+        "Quote" | "'" => Key::Unicode('\''),
+        "BracketLeft" | "[" => Key::Unicode('['),
+        "BracketRight" | "]" => Key::Unicode(']'),
+        "Minus" | "-" => Key::Unicode('-'),
+        "Equal" | "=" => Key::Unicode('='),
+        "Backquote" | "`" => Key::Unicode('`'),
+
+        "MediaPlayPause" => Key::MediaPlayPause,
+        #[cfg(not(target_os = "macos"))]
+        "MediaStop" => Key::MediaStop,
+        "MediaTrackNext" => Key::MediaNextTrack,
+        "MediaTrackPrevious" => Key::MediaPrevTrack,
+        "AudioVolumeUp" => Key::VolumeUp,
+        "AudioVolumeDown" => Key::VolumeDown,
+        "AudioVolumeMute" => Key::VolumeMute,
+
+        // Letters and digits are typed by character so they work on every layout
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphanumeric() => {
+                    Key::Unicode(c.to_ascii_lowercase())
+                }
+                _ => return None,
+            }
+        }
+    };
+    Some(key)
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn enigo_modifier(name: &str) -> Option<Key> {
+    match name {
+        "Ctrl" => Some(Key::Control),
+        "Alt" => Some(Key::Alt),
+        "Shift" => Some(Key::Shift),
+        "Cmd" | "Super" => Some(Key::Meta),
+        "CmdOrCtrl" if cfg!(target_os = "macos") => Some(Key::Meta),
+        "CmdOrCtrl" => Some(Key::Control),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn synthesize_click(pacer: &Pacer) -> Result<(), ControlsError> {
+    let mut enigo = new_enigo()?;
+    pacer.wait();
+    enigo
+        .button(Button::Left, Direction::Click)
+        .map_err(|e| ControlsError::Failed(format!("Failed to execute mouse click: {}", e)))
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn synthesize_key(pacer: &Pacer, key: &str, modifiers: &[String]) -> Result<(), ControlsError> {
+    let unsupported = |name: &str| ControlsError::Invalid(format!("Unsupported key: {}", name));
+    let key = enigo_key(key).ok_or_else(|| unsupported(key))?;
+    let modifiers = modifiers
+        .iter()
+        .map(|m| enigo_modifier(m).ok_or_else(|| unsupported(m)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut enigo = new_enigo()?;
+    let mut pressed = Vec::new();
+    let mut result = Ok(());
+    for modifier in modifiers {
+        pacer.wait();
+        if let Err(e) = enigo.key(modifier, Direction::Press) {
+            result = Err(ControlsError::Failed(format!(
+                "Failed to press modifier: {}",
+                e
+            )));
+            break;
+        }
+        pressed.push(modifier);
+    }
+    if result.is_ok() {
+        pacer.wait();
+        result = enigo
+            .key(key, Direction::Click)
+            .map_err(|e| ControlsError::Failed(format!("Failed to press key: {}", e)));
+    }
+    // Always release what was pressed so no modifier is left stuck down
+    for modifier in pressed.into_iter().rev() {
+        pacer.wait();
+        if let Err(e) = enigo.key(modifier, Direction::Release) {
+            log::warn!("Failed to release modifier {:?}: {}", modifier, e);
+        }
+    }
+    result
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn synthesize_text(pacer: &Pacer, text: &str) -> Result<(), ControlsError> {
+    let mut enigo = new_enigo()?;
+    let chars: Vec<char> = text.chars().collect();
+    // With a configured delay every character is its own event
+    let chunk_size = if pacer.delay.is_zero() {
+        TYPE_CHUNK_CHARS
+    } else {
+        1
+    };
+    for (i, chunk) in chars.chunks(chunk_size).enumerate() {
+        if i > 0 && pacer.delay.is_zero() {
+            std::thread::sleep(TYPE_CHUNK_DELAY);
+        }
+        pacer.wait();
+        let chunk: String = chunk.iter().collect();
+        enigo
+            .text(&chunk)
+            .map_err(|e| ControlsError::Failed(format!("Failed to type text: {}", e)))?;
+    }
+    Ok(())
+}
+
+// `scale_factor` converts the physical point to the points Enigo expects on macOS
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn synthesize_scroll(
+    pacer: &Pacer,
+    (x, y): (i32, i32),
+    scale_factor: f64,
+    (delta_x, delta_y): (i32, i32),
+) -> Result<(), ControlsError> {
+    let (x, y) = if cfg!(target_os = "macos") {
+        (
+            (x as f64 / scale_factor).round() as i32,
+            (y as f64 / scale_factor).round() as i32,
+        )
+    } else {
+        (x, y)
+    };
+
+    let mut enigo = new_enigo()?;
+    pacer.wait();
+    enigo
+        .move_mouse(x, y, Coordinate::Abs)
+        .map_err(|e| ControlsError::Failed(format!("Failed to move mouse: {}", e)))?;
+    for (length, axis) in [(delta_y, Axis::Vertical), (delta_x, Axis::Horizontal)] {
+        if length != 0 {
+            pacer.wait();
+            enigo
+                .scroll(length, axis)
+                .map_err(|e| ControlsError::Failed(format!("Failed to scroll: {}", e)))?;
         }
     }
+    Ok(())
 }
 
-/// Mobile stub for click handler - not supported on mobile
 #[cfg(any(target_os = "android", target_os = "ios"))]
-pub async fn click_handler(AxumState(_state): AxumState<AppState>) -> StatusCode {
-    log::warn!("Mouse control not available on mobile");
-    StatusCode::NOT_IMPLEMENTED
+fn synthesize_click(_pacer: &Pacer) -> Result<(), ControlsError> {
+    Err(ControlsError::Unsupported)
+}
+
+#[cfg(any(target_os = "android", target_os = "ios"))]
+fn synthesize_key(_pacer: &Pacer, _key: &str, _modifiers: &[String]) -> Result<(), ControlsError> {
+    Err(ControlsError::Unsupported)
+}
+
+#[cfg(any(target_os = "android", target_os = "ios"))]
+fn synthesize_text(_pacer: &Pacer, _text: &str) -> Result<(), ControlsError> {
+    Err(ControlsError::Unsupported)
+}
+
+#[cfg(any(target_os = "android", target_os = "ios"))]
+fn synthesize_scroll(
+    _pacer: &Pacer,
+    _position: (i32, i32),
+    _scale_factor: f64,
+    _delta: (i32, i32),
+) -> Result<(), ControlsError> {
+    Err(ControlsError::Unsupported)
+}
+
+/// Runs a synthesis off the async runtime, since pacing and chunking sleep
+async fn run_input<F>(app_handle: &AppHandle, synthesize: F) -> Result<(), ControlsError>
+where
+    F: FnOnce(&Pacer) -> Result<(), ControlsError> + Send + 'static,
+{
+    let pacer = Pacer::new(app_handle);
+    tauri::async_runtime::spawn_blocking(move || synthesize(&pacer))
+        .await
+        .map_err(|e| ControlsError::Failed(format!("Input task failed: {}", e)))?
+}
+
+async fn perform_key_press(
+    app_handle: &AppHandle,
+    key: String,
+    modifiers: Vec<String>,
+) -> Result<(), ControlsError> {
+    validate_key_press(&key, &modifiers)?;
+    run_input(app_handle, move |pacer| {
+        synthesize_key(pacer, &key, &modifiers)
+    })
+    .await
+}
+
+async fn perform_type_text(app_handle: &AppHandle, text: String) -> Result<(), ControlsError> {
+    let length = text.chars().count();
+    if length == 0 {
+        return Err(ControlsError::Invalid("Text is empty".to_string()));
+    }
+    if length > MAX_TYPE_TEXT_CHARS {
+        return Err(ControlsError::Invalid(format!(
+            "Text is too long ({} chars, max {})",
+            length, MAX_TYPE_TEXT_CHARS
+        )));
+    }
+
+    run_input(app_handle, move |pacer| synthesize_text(pacer, &text)).await
+}
+
+/// Scale factor of the monitor containing the physical point, or an error
+/// when the point is outside every monitor
+fn monitor_scale_factor_at(app_handle: &AppHandle, x: i32, y: i32) -> Result<f64, ControlsError> {
+    let monitors = app_handle
+        .available_monitors()
+        .map_err(|e| ControlsError::Failed(format!("Failed to list monitors: {}", e)))?;
+    monitors
+        .iter()
+        .find(|monitor| {
+            let position = monitor.position();
+            let size = monitor.size();
+            x >= position.x
+                && y >= position.y
+                && (x as i64) < position.x as i64 + size.width as i64
+                && (y as i64) < position.y as i64 + size.height as i64
+        })
+        .map(|monitor| monitor.scale_factor())
+        .ok_or_else(|| {
+            ControlsError::Invalid(format!("Position ({}, {}) is outside every monitor", x, y))
+        })
+}
+
+/// Scrolls at a physical position; positive deltas scroll down / right
+async fn perform_scroll_at(
+    app_handle: &AppHandle,
+    position: (i32, i32),
+    delta: (i32, i32),
+) -> Result<(), ControlsError> {
+    let scale_factor = monitor_scale_factor_at(app_handle, position.0, position.1)?;
+    let delta = (
+        delta.0.clamp(-MAX_SCROLL_DELTA, MAX_SCROLL_DELTA),
+        delta.1.clamp(-MAX_SCROLL_DELTA, MAX_SCROLL_DELTA),
+    );
+    if delta == (0, 0) {
+        return Err(ControlsError::Invalid("Scroll delta is zero".to_string()));
+    }
+
+    run_input(app_handle, move |pacer| {
+        synthesize_scroll(pacer, position, scale_factor, delta)
+    })
+    .await
+}
+
+/// Handler for /click endpoint
+/// Triggers a mouse click at the current cursor position (desktop only)
+pub async fn click_handler(
+    AxumState(state): AxumState<AppState>,
+    Query(query): Query<ControlsQuery>,
+    headers: HeaderMap,
+) -> StatusCode {
+    log::info!("Received click request from agent {:?}", query.agent_id);
+
+    let agent_id = query.agent_id.as_deref();
+    let result = match check_agent_access(&state, agent_id) {
+        Ok(()) => run_input(&state.app_handle, synthesize_click).await,
+        Err(e) => Err(e),
+    };
+    audit(&http_source(agent_id, &headers), "click", &result);
+
+    match result {
+        Ok(()) => StatusCode::OK,
+        Err(e) => e.status(),
+    }
+}
+
+/// Handler for /key endpoint
+/// Presses a key with optional modifiers, e.g. {"key": "Enter"} or {"key": "C", "modifiers": ["Ctrl"]}
+pub async fn key_handler(
+    AxumState(state): AxumState<AppState>,
+    Query(query): Query<ControlsQuery>,
+    headers: HeaderMap,
+    JsonBody(payload): JsonBody<KeyPayload>,
+) -> StatusCode {
+    log::info!(
+        "Received key request from agent {:?}: {}",
+        query.agent_id,
+        payload.key
+    );
+
+    let agent_id = query.agent_id.as_deref();
+    let action = format!("key {}", payload.key);
+    let modifiers = payload.modifiers.unwrap_or_default();
+    let result = match check_agent_access(&state, agent_id) {
+        Ok(()) => perform_key_press(&state.app_handle, payload.key, modifiers).await,
+        Err(e) => Err(e),
+    };
+    audit(&http_source(agent_id, &headers), &action, &result);
+
+    match result {
+        Ok(()) => StatusCode::OK,
+        Err(e) => e.status(),
+    }
+}
+
+/// Presses a key with optional modifiers ("Ctrl", "Alt", "Shift", "Cmd", "CmdOrCtrl")
+#[tauri::command]
+pub async fn perform_key(
+    key: String,
+    modifiers: Option<Vec<String>>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Performing key press: {} {:?}", key, modifiers);
+
+    let modifiers = modifiers.unwrap_or_default();
+    let action = format!("key {}", key);
+    let result = match ensure_controls_enabled(&app_handle) {
+        Ok(()) => perform_key_press(&app_handle, key, modifiers).await,
+        Err(e) => Err(e),
+    };
+    audit("app", &action, &result);
+    result.map_err(|e| e.to_string())
+}
+
+/// Handler for /type endpoint
+/// Types the given text into the focused field, e.g. {"text": "hello"}
+pub async fn type_handler(
+    AxumState(state): AxumState<AppState>,
+    Query(query): Query<ControlsQuery>,
+    headers: HeaderMap,
+    JsonBody(payload): JsonBody<TypePayload>,
+) -> StatusCode {
+    // Only the length is logged; the text itself may be sensitive
+    let action = format!("type {} chars", payload.text.chars().count());
+    log::info!(
+        "Received type request from agent {:?}: {}",
+        query.agent_id,
+        action
+    );
+
+    let agent_id = query.agent_id.as_deref();
+    let result = match check_agent_access(&state, agent_id) {
+        Ok(()) => perform_type_text(&state.app_handle, payload.text).await,
+        Err(e) => Err(e),
+    };
+    audit(&http_source(agent_id, &headers), &action, &result);
+
+    match result {
+        Ok(()) => StatusCode::OK,
+        Err(e) => e.status(),
+    }
+}
+
+/// Types a string into the focused field, chunking long input
+#[tauri::command]
+pub async fn type_text(text: String, app_handle: AppHandle) -> Result<(), String> {
+    let action = format!("type {} chars", text.chars().count());
+    log::info!("Performing {}", action);
+
+    let result = match ensure_controls_enabled(&app_handle) {
+        Ok(()) => perform_type_text(&app_handle, text).await,
+        Err(e) => Err(e),
+    };
+    audit("app", &action, &result);
+    result.map_err(|e| e.to_string())
+}
+
+/// Handler for /scroll endpoint
+/// Moves to a physical position and scrolls, e.g. {"x": 400, "y": 300, "delta_y": 3}
+pub async fn scroll_handler(
+    AxumState(state): AxumState<AppState>,
+    Query(query): Query<ControlsQuery>,
+    headers: HeaderMap,
+    JsonBody(payload): JsonBody<ScrollPayload>,
+) -> StatusCode {
+    let action = format!(
+        "scroll ({}, {}) by ({}, {})",
+        payload.x, payload.y, payload.delta_x, payload.delta_y
+    );
+    log::info!(
+        "Received scroll request from agent {:?}: {}",
+        query.agent_id,
+        action
+    );
+
+    let agent_id = query.agent_id.as_deref();
+    let result = match check_agent_access(&state, agent_id) {
+        Ok(()) => {
+            perform_scroll_at(
+                &state.app_handle,
+                (payload.x, payload.y),
+                (payload.delta_x, payload.delta_y),
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+    audit(&http_source(agent_id, &headers), &action, &result);
+
+    match result {
+        Ok(()) => StatusCode::OK,
+        Err(e) => e.status(),
+    }
+}
+
+/// Moves the cursor to (x, y) in physical pixels and scrolls by the given
+/// wheel notches (clamped to ±50); positive deltas scroll down / right
+#[tauri::command]
+pub async fn perform_scroll(
+    x: i32,
+    y: i32,
+    delta_x: i32,
+    delta_y: i32,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let action = format!("scroll ({}, {}) by ({}, {})", x, y, delta_x, delta_y);
+    log::info!("Performing {}", action);
+
+    let result = match ensure_controls_enabled(&app_handle) {
+        Ok(()) => perform_scroll_at(&app_handle, (x, y), (delta_x, delta_y)).await,
+        Err(e) => Err(e),
+    };
+    audit("app", &action, &result);
+    result.map_err(|e| e.to_string())
+}
+
+/// Current global cursor position in physical pixels, the same coordinate
+/// space as the overlay geometry commands (origin at the primary monitor's top-left)
+#[tauri::command]
+pub async fn get_cursor_position(app_handle: AppHandle) -> Result<(i32, i32), String> {
+    let position = app_handle
+        .cursor_position()
+        .map_err(|e| format!("Failed to get cursor position: {}", e))?;
+    Ok((position.x.round() as i32, position.y.round() as i32))
+}
+
+#[cfg(target_os = "windows")]
+fn foreground_window_title() -> Result<String, String> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowTextLengthW, GetWindowTextW,
+    };
+
+    // SAFETY: plain Win32 queries; the buffer is sized from GetWindowTextLengthW
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_null() {
+            return Err("No foreground window".to_string());
+        }
+        let length = GetWindowTextLengthW(hwnd);
+        let mut buffer = vec![0u16; length.max(0) as usize + 1];
+        let copied = GetWindowTextW(hwnd, buffer.as_mut_ptr(), buffer.len() as i32);
+        Ok(String::from_utf16_lossy(&buffer[..copied.max(0) as usize]))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn foreground_window_title() -> Result<String, String> {
+    // Needs the Accessibility permission; falls back to the app name when the
+    // frontmost app exposes no window
+    const SCRIPT: &str = r#"tell application "System Events"
+    set frontApp to first application process whose frontmost is true
+    try
+        return name of front window of frontApp
+    on error
+        return name of frontApp
+    end try
+end tell"#;
+    run_title_command("osascript", &["-e", SCRIPT])
+}
+
+#[cfg(target_os = "linux")]
+fn foreground_window_title() -> Result<String, String> {
+    // Wayland doesn't let clients inspect other windows, so only X11 is supported
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() && std::env::var_os("DISPLAY").is_none() {
+        return Err("Active window title is not available on Wayland".to_string());
+    }
+    run_title_command("xdotool", &["getactivewindow", "getwindowname"])
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn run_title_command(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn foreground_window_title() -> Result<String, String> {
+    Err("Active window title is not supported on this platform".to_string())
+}
+
+/// Title of the window that currently has focus, queried off the async runtime
+pub async fn active_window_title() -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(foreground_window_title)
+        .await
+        .map_err(|e| format!("Window title task failed: {}", e))?
+}
+
+/// Title of the window that currently has focus (X11 only on Linux)
+#[tauri::command]
+pub async fn get_active_window_title() -> Result<String, String> {
+    active_window_title().await
+}
+
+#[derive(Serialize)]
+pub struct ControlsCapabilities {
+    // Kill-switch state; operations below are refused while this is false
+    enabled: bool,
+    click: bool,
+    key: bool,
+    type_text: bool,
+    scroll: bool,
+    cursor_position: bool,
+    active_window_title: bool,
+}
+
+// Whether an Enigo backend can be created here, e.g. false on Wayland without
+// libei or on mobile
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn input_synthesis_available() -> bool {
+    new_enigo().is_ok()
+}
+
+#[cfg(any(target_os = "android", target_os = "ios"))]
+fn input_synthesis_available() -> bool {
+    false
+}
+
+// Mirrors the platform checks in foreground_window_title; on Linux it assumes
+// xdotool is installed
+fn active_window_title_available() -> bool {
+    if cfg!(any(target_os = "windows", target_os = "macos")) {
+        true
+    } else if cfg!(target_os = "linux") {
+        std::env::var_os("WAYLAND_DISPLAY").is_none() || std::env::var_os("DISPLAY").is_some()
+    } else {
+        false
+    }
+}
+
+/// Which input automation operations work on this platform and build, so the
+/// UI can hide the ones that can't
+#[tauri::command]
+pub async fn get_controls_capabilities(
+    app_handle: AppHandle,
+) -> Result<ControlsCapabilities, String> {
+    let input = tauri::async_runtime::spawn_blocking(input_synthesis_available)
+        .await
+        .map_err(|e| format!("Capability probe failed: {}", e))?;
+
+    Ok(ControlsCapabilities {
+        enabled: ensure_controls_enabled(&app_handle).is_ok(),
+        click: input,
+        key: input,
+        type_text: input,
+        scroll: input,
+        cursor_position: cfg!(desktop),
+        active_window_title: active_window_title_available(),
+    })
+}
+
+/// Turns synthesized input on or off. It ships off, so agents can only drive
+/// the mouse and keyboard after the user opts in here
+#[tauri::command]
+pub async fn set_controls_enabled(
+    enabled: bool,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!(target: AUDIT_TARGET, "Setting controls_enabled to {}", enabled);
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        config.controls_enabled = enabled;
+    })
 }
@@ -0,0 +1,50 @@
+// In src-tauri/src/extract.rs
+
+use axum::{
+    async_trait,
+    extract::{rejection::JsonRejection, FromRequest, Request},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use serde::de::DeserializeOwned;
+
+/// Drop-in replacement for `axum::Json` on request bodies that answers malformed
+/// payloads with a structured 400 instead of axum's terse plain-text rejection
+pub struct JsonBody<T>(pub T);
+
+pub struct JsonBodyRejection(JsonRejection);
+
+impl IntoResponse for JsonBodyRejection {
+    fn into_response(self) -> Response {
+        let error = match &self.0 {
+            JsonRejection::JsonDataError(_) | JsonRejection::JsonSyntaxError(_) => "invalid_json",
+            JsonRejection::MissingJsonContentType(_) => "missing_content_type",
+            _ => "invalid_body",
+        };
+        let detail = self.0.body_text();
+
+        log::warn!("Rejected request body ({}): {}", error, detail);
+
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": error, "detail": detail })),
+        )
+            .into_response()
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequest<S> for JsonBody<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = JsonBodyRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match Json::<T>::from_request(req, state).await {
+            Ok(Json(value)) => Ok(JsonBody(value)),
+            Err(rejection) => Err(JsonBodyRejection(rejection)),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// In src-tauri/src/keymap.rs
+
+use crate::shortcuts::{self, UnifiedShortcutConfig, UnifiedShortcutState};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+
+// Portable keymap schema, independent of the AppConfig layout:
+// { "version": 1, "bindings": [ { "keys": "Alt+B", "action": "overlay_toggle" } ] }
+// Actions use the names from UnifiedShortcutConfig::all_bindings
+// ("overlay_*", "agent:<id>", "broadcast:<action>")
+const KEYMAP_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Keymap {
+    #[serde(default = "default_version")]
+    version: u32,
+    bindings: Vec<KeymapBinding>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct KeymapBinding {
+    keys: String,
+    action: String,
+}
+
+fn default_version() -> u32 {
+    KEYMAP_VERSION
+}
+
+/// Portable keymap for a shortcut config, sorted by action so it diffs cleanly
+pub fn to_keymap(config: &UnifiedShortcutConfig) -> Keymap {
+    let mut bindings: Vec<KeymapBinding> = config
+        .all_bindings()
+        .into_iter()
+        .map(|(action, keys)| KeymapBinding { keys, action })
+        .collect();
+    bindings.sort_by(|a, b| a.action.cmp(&b.action));
+
+    Keymap {
+        version: KEYMAP_VERSION,
+        bindings,
+    }
+}
+
+/// Builds a shortcut config holding exactly the keymap's bindings, collecting
+/// every problem rather than stopping at the first
+pub fn from_keymap(keymap: &Keymap) -> Result<UnifiedShortcutConfig, Vec<String>> {
+    if keymap.version != KEYMAP_VERSION {
+        return Err(vec![format!(
+            "Unsupported keymap version {} (expected {})",
+            keymap.version, KEYMAP_VERSION
+        )]);
+    }
+
+    let mut config = UnifiedShortcutConfig::default();
+    for (_, binding) in config.overlay_bindings_mut() {
+        *binding = None;
+    }
+    config.agent_shortcuts.clear();
+    config.agent_broadcast_shortcuts.clear();
+
+    let mut errors = Vec::new();
+    // Keyed by the parsed shortcut so spellings of the same keys (e.g.
+    // CmdOrCtrl+K and Ctrl+K off macOS) conflict
+    let mut actions_by_shortcut: HashMap<tauri_plugin_global_shortcut::Shortcut, String> =
+        HashMap::new();
+
+    for binding in &keymap.bindings {
+        let keys = match shortcuts::normalize_shortcut(&binding.keys) {
+            Ok(keys) => keys,
+            Err(e) => {
+                errors.push(format!("{}: {}", binding.action, e));
+                continue;
+            }
+        };
+
+        let shortcut = shortcuts::parse_shortcut_string(&keys);
+        if let Some(other) = shortcut.and_then(|shortcut| actions_by_shortcut.get(&shortcut)) {
+            errors.push(format!(
+                "{}: {} is already bound to {}",
+                binding.action, keys, other
+            ));
+            continue;
+        }
+
+        match config.set_binding(&binding.action, Some(keys)) {
+            Ok(Some(_)) => errors.push(format!("{}: bound more than once", binding.action)),
+            Ok(None) => {
+                if let Some(shortcut) = shortcut {
+                    actions_by_shortcut.insert(shortcut, binding.action.clone());
+                }
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(config)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Current shortcuts as portable keymap JSON
+#[tauri::command]
+pub async fn export_keymap(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<String, String> {
+    let config = shortcut_state.config.lock().unwrap().shortcuts.clone();
+    serde_json::to_string_pretty(&to_keymap(&config)).map_err(|e| e.to_string())
+}
+
+/// Replaces all shortcuts with the bindings from keymap JSON and re-registers
+/// them; nothing is changed when any binding is invalid. The keymap can't
+/// express app conditions, so agents that stay bound keep theirs
+#[tauri::command]
+pub async fn import_keymap(
+    json: String,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<UnifiedShortcutConfig, Vec<String>> {
+    let keymap: Keymap =
+        serde_json::from_str(&json).map_err(|e| vec![format!("Invalid keymap JSON: {}", e)])?;
+    let mut shortcuts = from_keymap(&keymap)?;
+
+    log::info!("Importing keymap with {} binding(s)", keymap.bindings.len());
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        shortcuts::keep_agent_conditions(&mut shortcuts, &config.shortcuts);
+        config.shortcuts = shortcuts.clone();
+    })
+    .map_err(|e| vec![e])?;
+    shortcuts::refresh_shortcuts(&app_handle).map_err(|e| vec![e])?;
+
+    Ok(shortcuts)
+}
@@ -2,22 +2,34 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod backend;
 mod commands;
+mod config_diff;
 mod controls;
+mod extract;
+mod keymap;
+mod logging;
 mod notifications;
 mod overlay;
+mod runtime_state;
 mod shortcuts;
+mod tasks;
+mod validation;
+mod window_close;
 
 // Import unified shortcut types (desktop only)
 use shortcuts::UnifiedShortcutState;
 
 // ---- Final, Corrected Imports (Desktop only) ----
 use axum::{
-    body::Body,
-    extract::State as AxumState,
-    http::{HeaderMap, Method, StatusCode, Uri},
+    body::{Body, Bytes},
+    extract::{DefaultBodyLimit, Request, State as AxumState},
+    http::{
+        header::{ACCESS_CONTROL_REQUEST_METHOD, AUTHORIZATION, ORIGIN},
+        HeaderMap, HeaderName, HeaderValue, Method, StatusCode,
+    },
     response::Response,
-    routing::any,
+    routing::{any, MethodRouter},
     Router,
 };
 use futures::future::join_all;
@@ -25,13 +37,13 @@ use futures::future::join_all;
 use http_body_util::BodyExt;
 
 use reqwest::Client;
-use std::sync::Mutex;
-use tauri::{AppHandle, Emitter, Manager, State};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State, Wry};
 
 use tauri::{
     menu::{Menu, MenuItem},
     tray::TrayIconBuilder,
-    WebviewUrl, WebviewWindowBuilder,
 };
 
 use tauri_plugin_dialog::DialogExt;
@@ -39,12 +51,13 @@ use tauri_plugin_dialog::DialogExt;
 use tauri_plugin_updater::UpdaterExt;
 
 use tower_http::{
-    cors::{Any, CorsLayer},
+    cors::{AllowOrigin, Any, CorsLayer},
     services::ServeDir,
 };
 
 struct AppSettings {
     ollama_url: Mutex<Option<String>>,
+    ollama_api_key: Mutex<Option<String>>,
 }
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
@@ -52,27 +65,53 @@ pub struct OverlayMessage {
     id: String,
     content: String,
     timestamp: u64,
+    #[serde(default)]
+    agent_id: Option<String>,
+    // Resolved from the per-agent config when the message is tagged with an agent
+    #[serde(default)]
+    color: Option<String>,
+    #[serde(default)]
+    icon: Option<String>,
+    // Which subsystem produced the message, e.g. "system", "updater" or "agent:<id>"
+    #[serde(default)]
+    source: Option<String>,
 }
 
 struct OverlayState {
     messages: Mutex<Vec<OverlayMessage>>,
+    // Bumped on every overlay move so only the last one is persisted
+    position_saves: AtomicU64,
 }
 
 use tokio::sync::broadcast;
 
-#[derive(Clone, serde::Serialize, Debug)]
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
 pub struct CommandMessage {
     #[serde(rename = "type")]
     pub message_type: String,
     #[serde(rename = "agentId")]
     pub agent_id: String,
     pub action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<serde_json::Value>,
+    // History id of the command this re-sends, so agents can tell replays apart
+    #[serde(rename = "replayOf", default, skip_serializing_if = "Option::is_none")]
+    pub replay_of: Option<String>,
 }
 
 struct CommandState {
     pending_commands: Mutex<std::collections::HashMap<String, String>>,
-    // SSE broadcast channel for real-time commands
-    command_broadcaster: broadcast::Sender<CommandMessage>,
+    // SSE broadcast channel for real-time commands, tagged with their event id
+    command_broadcaster: broadcast::Sender<(u64, CommandMessage)>,
+    // Id of the last SSE event sent; also serializes sends so ids reach
+    // subscribers and the history in order
+    last_event_id: Mutex<u64>,
+    // While paused, commands are held here instead of being broadcast (at
+    // most MAX_HELD_COMMANDS, oldest first)
+    broadcaster_paused: AtomicBool,
+    held_commands: Mutex<std::collections::VecDeque<commands::HeldCommand>>,
+    // Most recent broadcasts, oldest first
+    history: Mutex<std::collections::VecDeque<commands::CommandRecord>>,
 }
 
 #[tauri::command]
@@ -84,11 +123,11 @@ async fn set_ollama_url(
 ) -> Result<(), String> {
     log::info!("Setting Ollama URL to: {:?}", new_url);
 
-    // Update in-memory AppSettings
-    *settings.ollama_url.lock().unwrap() = new_url.clone();
+    // Persist to disk first (also updates UnifiedShortcutState); fails if the config is locked
+    shortcuts::save_ollama_url(&app_handle, &shortcut_state, new_url.clone())?;
 
-    // Persist to disk (also updates UnifiedShortcutState)
-    shortcuts::save_ollama_url(&app_handle, &shortcut_state, new_url)?;
+    // Update in-memory AppSettings
+    *settings.ollama_url.lock().unwrap() = new_url;
 
     Ok(())
 }
@@ -103,15 +142,198 @@ async fn get_ollama_url(settings: State<'_, AppSettings>) -> Result<Option<Strin
 }
 
 #[tauri::command]
-async fn check_ollama_servers(urls: Vec<String>) -> Result<Vec<String>, String> {
-    // <-- No State parameter
+async fn set_ollama_api_key(
+    api_key: Option<String>,
+    settings: State<'_, AppSettings>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Setting Ollama API key (present: {})", api_key.is_some());
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        config.ollama_api_key = api_key.clone();
+    })?;
+
+    *settings.ollama_api_key.lock().unwrap() = api_key;
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_ollama_api_key(settings: State<'_, AppSettings>) -> Result<Option<String>, String> {
+    let api_key = settings.ollama_api_key.lock().unwrap().clone();
+    Ok(api_key)
+}
+
+#[tauri::command]
+async fn get_start_hidden(shortcut_state: State<'_, UnifiedShortcutState>) -> Result<bool, String> {
+    Ok(shortcut_state.config.lock().unwrap().start_hidden)
+}
+
+#[tauri::command]
+async fn set_start_hidden(
+    enabled: bool,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Setting start_hidden to {}", enabled);
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        config.start_hidden = enabled;
+    })
+}
+
+const THEMES: [&str; 3] = ["light", "dark", "system"];
+
+#[tauri::command]
+async fn get_theme(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<Option<String>, String> {
+    Ok(shortcut_state.config.lock().unwrap().theme.clone())
+}
+
+/// Persists the theme preference and emits `theme-changed` to every window
+#[tauri::command]
+async fn set_theme(
+    theme: Option<String>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Setting theme to {:?}", theme);
+
+    if let Some(theme) = &theme {
+        if !THEMES.contains(&theme.as_str()) {
+            return Err(format!(
+                "Invalid theme: {} (expected one of {})",
+                theme,
+                THEMES.join(", ")
+            ));
+        }
+    }
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        config.theme = theme.clone();
+    })?;
+
+    if let Err(e) = app_handle.emit("theme-changed", &theme) {
+        log::warn!("Failed to emit theme-changed event: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Persists the config, stops background tasks and restarts the app,
+/// optionally after the user confirms in a dialog
+#[tauri::command]
+async fn relaunch_app(
+    confirm: Option<bool>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    if confirm.unwrap_or(false) {
+        let dialog_handle = app_handle.clone();
+        let confirmed = tokio::task::spawn_blocking(move || {
+            dialog_handle
+                .dialog()
+                .message("Observer will restart to apply your changes.")
+                .title("Restart Observer")
+                .buttons(tauri_plugin_dialog::MessageDialogButtons::OkCancel)
+                .blocking_show()
+        })
+        .await
+        .unwrap_or(false);
+        if !confirmed {
+            log::info!("Relaunch cancelled by user");
+            return Ok(());
+        }
+    }
+
+    log::info!("Relaunching application");
+    // Flush the in-memory config so nothing is lost across the restart
+    // (a locked config can't have changed)
+    if !shortcuts::is_config_locked(&shortcut_state.config.lock().unwrap()) {
+        shortcuts::update_config(&app_handle, &shortcut_state, |_| {})?;
+    }
+    app_handle.state::<tasks::BackgroundTasks>().cancel_all();
+
+    app_handle.restart()
+}
+
+// Configured User-Agent, or one identifying Observer and its version
+fn user_agent(app_handle: &AppHandle) -> String {
+    let configured = app_handle
+        .state::<UnifiedShortcutState>()
+        .config
+        .lock()
+        .unwrap()
+        .user_agent
+        .clone();
+    configured.unwrap_or_else(|| format!("Observer/{}", app_handle.package_info().version))
+}
+
+// `idle_timeout_secs` bounds how long unused pooled connections are kept:
+// None keeps reqwest's default, 0 disables pooling of idle connections
+fn build_http_client(user_agent: &str, idle_timeout_secs: Option<u64>) -> Client {
+    let mut builder = Client::builder().user_agent(user_agent);
+    match idle_timeout_secs {
+        Some(0) => builder = builder.pool_max_idle_per_host(0),
+        Some(secs) => builder = builder.pool_idle_timeout(std::time::Duration::from_secs(secs)),
+        None => {}
+    }
+    builder.build().unwrap_or_else(|e| {
+        log::warn!(
+            "Failed to build HTTP client with user agent, using defaults: {}",
+            e
+        );
+        Client::new()
+    })
+}
+
+#[tauri::command]
+async fn get_user_agent(app_handle: AppHandle) -> Result<String, String> {
+    Ok(user_agent(&app_handle))
+}
+
+/// Persists a User-Agent override (None restores the default) and rebuilds the
+/// shared proxy client so it applies to the next request
+#[tauri::command]
+async fn set_user_agent(
+    user_agent: Option<String>,
+    http_client: State<'_, HttpClientState>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Setting user agent to {:?}", user_agent);
+    if let Some(user_agent) = &user_agent {
+        HeaderValue::from_str(user_agent).map_err(|_| "Invalid user agent".to_string())?;
+    }
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        config.user_agent = user_agent;
+    })?;
+    let idle_timeout_secs = shortcut_state
+        .config
+        .lock()
+        .unwrap()
+        .proxy_idle_timeout_secs;
+    http_client.replace(build_http_client(
+        &crate::user_agent(&app_handle),
+        idle_timeout_secs,
+    ));
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn check_ollama_servers(
+    urls: Vec<String>,
+    app_handle: AppHandle,
+) -> Result<Vec<String>, String> {
     log::info!(
         "Rust backend received request to check servers (using dedicated client): {:?}",
         urls
     );
 
     // Create a new, temporary client just for this operation.
-    let client = Client::new();
+    let client = build_http_client(&user_agent(&app_handle), None);
 
     // The rest of the logic is identical.
     let checks = urls.into_iter().map(|url| {
@@ -156,8 +378,10 @@ async fn check_ollama_servers(urls: Vec<String>) -> Result<Vec<String>, String>
 #[tauri::command]
 async fn get_overlay_messages(
     overlay_state: State<'_, OverlayState>,
+    app_handle: tauri::AppHandle,
 ) -> Result<Vec<OverlayMessage>, String> {
     log::info!("Getting overlay messages");
+    overlay::ensure_overlay_enabled(&app_handle)?;
     let messages = overlay_state.messages.lock().unwrap().clone();
     Ok(messages)
 }
@@ -167,20 +391,8 @@ async fn clear_overlay_messages(
     overlay_state: State<'_, OverlayState>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
-    log::info!("Clearing overlay messages");
-    overlay_state.messages.lock().unwrap().clear();
-
-    // Emit event to notify frontend of cleared messages
-    let empty_messages: Vec<OverlayMessage> = vec![];
-    if let Err(e) = app_handle.emit("overlay-messages-updated", &empty_messages) {
-        log::warn!(
-            "Failed to emit overlay-messages-updated event after clear: {}",
-            e
-        );
-    } else {
-        log::debug!("Emitted overlay-messages-updated event with 0 messages after clear");
-    }
-
+    overlay::ensure_overlay_enabled(&app_handle)?;
+    overlay::clear_overlay(&app_handle, &overlay_state);
     Ok(())
 }
 
@@ -188,40 +400,114 @@ async fn clear_overlay_messages(
 
 // Shortcut helper functions moved to shortcuts module
 
-// Shared state for our application (desktop only)
-#[derive(Clone)]
-struct AppState {
-    app_handle: AppHandle,
-    http_client: Client,
+// Shared state for our application (desktop only). Generic over the runtime
+// so the proxy can be driven from a mock app in tests
+struct AppState<R: Runtime = Wry> {
+    app_handle: AppHandle<R>,
 }
 
-async fn proxy_handler(
-    AxumState(state): AxumState<AppState>,
-    method: Method,
-    headers: HeaderMap,
-    uri: Uri,
-    body: Body,
-) -> Result<Response, StatusCode> {
-    let path = uri.path();
-    let query = uri.query().unwrap_or("");
+impl<R: Runtime> Clone for AppState<R> {
+    fn clone(&self) -> Self {
+        Self {
+            app_handle: self.app_handle.clone(),
+        }
+    }
+}
 
-    let target_url = {
-        // This whole block will evaluate to a single String value.
+// Single HTTP client shared by the proxy and backend commands so they share a
+// connection pool; replaced as a whole when the pool settings change
+struct HttpClientState {
+    client: RwLock<Client>,
+    // Times the client was rebuilt since startup
+    rebuilds: AtomicU64,
+}
 
-        let settings = state.app_handle.state::<AppSettings>();
-        let ollama_url_guard = settings.ollama_url.lock().unwrap();
+impl HttpClientState {
+    fn new(client: Client) -> Self {
+        Self {
+            client: RwLock::new(client),
+            rebuilds: AtomicU64::new(0),
+        }
+    }
 
-        let base_url = ollama_url_guard
-            .as_deref()
-            .unwrap_or("http://127.0.0.1:11434");
+    // Clients are cheap handles onto the pool, so callers take their own copy
+    fn client(&self) -> Client {
+        self.client.read().unwrap().clone()
+    }
 
-        // 2. This is the last line. With no semicolon, its value is "returned"
-        //    from the block and assigned to `target_url`.
-        format!("{}{}?{}", base_url, path, query)
-    };
+    fn replace(&self, client: Client) {
+        *self.client.write().unwrap() = client;
+        self.rebuilds.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+// Resolves the configured Ollama base URL, falling back to the local default
+fn ollama_base_url<R: Runtime>(app_handle: &AppHandle<R>) -> String {
+    let settings = app_handle.state::<AppSettings>();
+    let ollama_url = settings.ollama_url.lock().unwrap();
+    ollama_url
+        .as_deref()
+        .unwrap_or("http://127.0.0.1:11434")
+        .to_string()
+}
 
-    log::info!("Proxying {} request to: {}", method, target_url);
+// API key for the backend at `base_url`: its own key from the failover list
+// when set, the global key otherwise
+fn ollama_api_key_for<R: Runtime>(app_handle: &AppHandle<R>, base_url: &str) -> Option<String> {
+    let backend_key = app_handle
+        .state::<UnifiedShortcutState>()
+        .config
+        .lock()
+        .unwrap()
+        .ollama_failover_urls
+        .iter()
+        .find(|backend| backend.url == base_url)
+        .and_then(|backend| backend.api_key.clone());
+    backend_key.or_else(|| {
+        app_handle
+            .state::<AppSettings>()
+            .ollama_api_key
+            .lock()
+            .unwrap()
+            .clone()
+    })
+}
+
+/// Parses a configured extra proxy header, rejecting invalid names and values
+fn parse_extra_header(name: &str, value: &str) -> Result<(HeaderName, HeaderValue), String> {
+    let header_name = HeaderName::from_bytes(name.as_bytes())
+        .map_err(|e| format!("Invalid header name '{}': {}", name, e))?;
+    let header_value = HeaderValue::from_str(value)
+        .map_err(|e| format!("Invalid value for header '{}': {}", name, e))?;
+    Ok((header_name, header_value))
+}
 
+// Adds the configured extra headers the caller didn't set itself
+fn apply_proxy_extra_headers<R: Runtime>(app_handle: &AppHandle<R>, headers: &mut HeaderMap) {
+    let extra_headers = app_handle
+        .state::<UnifiedShortcutState>()
+        .config
+        .lock()
+        .unwrap()
+        .proxy_extra_headers
+        .clone();
+    for (name, value) in &extra_headers {
+        match parse_extra_header(name, value) {
+            Ok((name, value)) => {
+                if !headers.contains_key(&name) {
+                    headers.insert(name, value);
+                }
+            }
+            Err(e) => log::warn!("Skipping extra proxy header: {}", e),
+        }
+    }
+}
+
+async fn proxy_handler<R: Runtime>(
+    AxumState(state): AxumState<AppState<R>>,
+    request: Request,
+) -> Result<Response, StatusCode> {
+    let (mut parts, body) = request.into_parts();
     let body_bytes = match body.collect().await {
         Ok(collected) => collected.to_bytes(),
         Err(e) => {
@@ -230,8 +516,70 @@ async fn proxy_handler(
         }
     };
 
-    let reqwest_request = state
-        .http_client
+    let client_label = observer_client_label(&parts.headers);
+    let base_url = ollama_base_url(&state.app_handle);
+    let api_key = ollama_api_key_for(&state.app_handle, &base_url);
+    apply_proxy_extra_headers(&state.app_handle, &mut parts.headers);
+
+    forward_to_backend(
+        &state.app_handle.state::<HttpClientState>().client(),
+        &client_label,
+        &base_url,
+        api_key.as_deref(),
+        Request::from_parts(parts, body_bytes),
+    )
+    .await
+}
+
+// Header callers can set to identify themselves in the proxy logs
+const OBSERVER_CLIENT_HEADER: &str = "x-observer-client";
+
+/// Caller label from the X-Observer-Client header, "unknown" when absent
+fn observer_client_label(headers: &HeaderMap) -> String {
+    headers
+        .get(OBSERVER_CLIENT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// Forwards a request to the backend at `base_url` and streams the response back.
+/// Kept free of AppHandle state so it can be driven directly against a mock backend.
+async fn forward_to_backend(
+    client: &Client,
+    client_label: &str,
+    base_url: &str,
+    api_key: Option<&str>,
+    request: Request<Bytes>,
+) -> Result<Response, StatusCode> {
+    let (parts, body_bytes) = request.into_parts();
+    let (method, mut headers) = (parts.method, parts.headers);
+    let path = parts.uri.path();
+    let query = parts.uri.query().unwrap_or("");
+
+    let target_url = format!("{}{}?{}", base_url, path, query);
+
+    log::info!(
+        "Proxying {} request from {} to: {}",
+        method,
+        client_label,
+        target_url
+    );
+
+    // Inject the configured API key unless the caller brought its own credentials
+    if !headers.contains_key(AUTHORIZATION) {
+        if let Some(api_key) = api_key {
+            match HeaderValue::from_str(&format!("Bearer {}", api_key)) {
+                Ok(value) => {
+                    headers.insert(AUTHORIZATION, value);
+                }
+                Err(e) => log::warn!("Configured Ollama API key is not a valid header: {}", e),
+            }
+        }
+    }
+
+    let reqwest_request = client
         .request(method, &target_url)
         .headers(headers)
         .body(body_bytes);
@@ -252,7 +600,11 @@ async fn proxy_handler(
             Ok(response_builder.body(response_body).unwrap())
         }
         Err(e) => {
-            log::error!("Proxy request to Ollama failed: {}", e);
+            log::error!(
+                "Proxy request to Ollama failed (client: {}): {}",
+                client_label,
+                e
+            );
             Err(StatusCode::BAD_GATEWAY)
         }
     }
@@ -266,12 +618,297 @@ fn get_server_url(server_url: State<Mutex<ServerUrl>>) -> String {
     server_url.lock().unwrap().0.clone()
 }
 
-#[cfg(all(not(debug_assertions)))]
+// Set while the release-build static server is listening
+#[derive(Default)]
+struct StaticServerStatus(AtomicBool);
+
+#[derive(serde::Serialize)]
+struct BuildInfo {
+    version: String,
+    // "debug" or "release"
+    profile: &'static str,
+    static_server_running: bool,
+    server_url: String,
+    desktop: bool,
+    mobile: bool,
+    target_os: &'static str,
+}
+
+#[tauri::command]
+fn get_build_info(
+    server_url: State<Mutex<ServerUrl>>,
+    server_status: State<StaticServerStatus>,
+    app_handle: AppHandle,
+) -> BuildInfo {
+    BuildInfo {
+        version: app_handle.package_info().version.to_string(),
+        profile: if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        },
+        static_server_running: server_status.0.load(Ordering::SeqCst),
+        server_url: server_url.lock().unwrap().0.clone(),
+        desktop: cfg!(desktop),
+        mobile: cfg!(mobile),
+        target_os: std::env::consts::OS,
+    }
+}
+
+// When the app started, recorded at the beginning of setup
+struct StartTime {
+    instant: std::time::Instant,
+    // Milliseconds since the Unix epoch
+    started_at: u64,
+}
+
+impl StartTime {
+    fn now() -> Self {
+        Self {
+            instant: std::time::Instant::now(),
+            started_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct UptimeInfo {
+    started_at: u64,
+    uptime_secs: u64,
+}
+
+/// When the app started and how long it has been running
+#[tauri::command]
+fn get_uptime(start_time: State<StartTime>) -> UptimeInfo {
+    UptimeInfo {
+        started_at: start_time.started_at,
+        uptime_secs: start_time.instant.elapsed().as_secs(),
+    }
+}
+
+// The local server only ever binds to loopback
+const SERVER_HOST: &str = "127.0.0.1";
+const SERVER_PORT: u16 = 3838;
+
+// Request body limit for buffered extractors (e.g. JSON); the proxy collects
+// bodies itself and is not limited
+const SERVER_BODY_LIMIT: usize = 2 * 1024 * 1024;
+
+type RouteHandler = fn() -> MethodRouter<AppState>;
+
+// Which browser origins may call a route. Routes that synthesize input or
+// expose what the user is doing are only served to the app's own pages, not
+// to every site the user has open
+#[derive(Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RouteAccess {
+    AnyOrigin,
+    AppOrigin,
+}
+
+// (method, path, access, handler) for every route the local server registers;
+// both start_static_server and describe_server read this table. Anything else
+// falls through to the static files
+const SERVER_ROUTES: [(&str, &str, RouteAccess, RouteHandler); 17] = [
+    ("ANY", "/v1/*path", RouteAccess::AnyOrigin, || {
+        any(proxy_handler)
+    }),
+    ("ANY", "/api/*path", RouteAccess::AnyOrigin, || {
+        any(proxy_handler)
+    }),
+    ("POST", "/ask", RouteAccess::AnyOrigin, || {
+        axum::routing::post(notifications::ask_handler)
+    }),
+    ("GET", "/ping", RouteAccess::AnyOrigin, || {
+        axum::routing::get(|| async {
+            log::info!("==== PING-PONG ====");
+            "pong"
+        })
+    }),
+    ("POST", "/message", RouteAccess::AnyOrigin, || {
+        axum::routing::post(notifications::message_handler)
+    }),
+    ("POST", "/notification", RouteAccess::AnyOrigin, || {
+        axum::routing::post(notifications::notification_handler)
+    }),
+    ("POST", "/overlay", RouteAccess::AnyOrigin, || {
+        axum::routing::post(overlay::overlay_handler)
+    }),
+    ("GET", "/overlay", RouteAccess::AppOrigin, || {
+        axum::routing::get(overlay::overlay_messages_handler)
+    }),
+    ("POST", "/click", RouteAccess::AppOrigin, || {
+        axum::routing::post(controls::click_handler)
+    }),
+    ("POST", "/key", RouteAccess::AppOrigin, || {
+        axum::routing::post(controls::key_handler)
+    }),
+    ("POST", "/type", RouteAccess::AppOrigin, || {
+        axum::routing::post(controls::type_handler)
+    }),
+    ("POST", "/scroll", RouteAccess::AppOrigin, || {
+        axum::routing::post(controls::scroll_handler)
+    }),
+    ("GET", "/agents", RouteAccess::AnyOrigin, || {
+        axum::routing::get(commands::agents_handler)
+    }),
+    ("GET", "/logs/stream", RouteAccess::AppOrigin, || {
+        axum::routing::get(logging::logs_stream_handler)
+    }),
+    ("GET", "/commands-stream", RouteAccess::AnyOrigin, || {
+        axum::routing::get(commands::commands_stream_handler)
+    }),
+    // Legacy HTTP endpoints (for backward compatibility during migration)
+    ("GET", "/commands", RouteAccess::AnyOrigin, || {
+        axum::routing::get(commands::get_commands_handler)
+    }),
+    ("POST", "/commands", RouteAccess::AnyOrigin, || {
+        axum::routing::post(commands::post_commands_handler)
+    }),
+];
+
+// Origins of the app's own pages: the bundled webview (tauri://localhost on
+// macOS/Linux, http(s)://tauri.localhost on Windows) and the frontend this
+// server hosts
+fn app_origins() -> [String; 5] {
+    [
+        "tauri://localhost".to_string(),
+        "http://tauri.localhost".to_string(),
+        "https://tauri.localhost".to_string(),
+        format!("http://{}:{}", SERVER_HOST, SERVER_PORT),
+        format!("http://localhost:{}", SERVER_PORT),
+    ]
+}
+
+// Whether a request from `origin` may reach the route it targets. Requests
+// without an Origin header come from local processes rather than web pages
+// and are let through. Only the release server calls this
+#[cfg_attr(debug_assertions, allow(dead_code))]
+fn origin_allowed(method: &Method, path: &str, origin: Option<&HeaderValue>) -> bool {
+    let app_only = SERVER_ROUTES
+        .iter()
+        .any(|&(route_method, route_path, access, _)| {
+            access == RouteAccess::AppOrigin
+                && route_path == path
+                && (route_method == "ANY" || route_method == method.as_str())
+        });
+    match origin {
+        Some(origin) if app_only => origin
+            .to_str()
+            .is_ok_and(|origin| app_origins().iter().any(|app| app == origin)),
+        _ => true,
+    }
+}
+
+// Rejects requests to app-only routes from other origins before they reach the
+// handler; CORS alone would still let a simple request through
+#[cfg(not(debug_assertions))]
+async fn require_app_origin(
+    request: Request,
+    next: axum::middleware::Next,
+) -> Result<Response, StatusCode> {
+    let origin = request.headers().get(ORIGIN);
+    if origin_allowed(request.method(), request.uri().path(), origin) {
+        return Ok(next.run(request).await);
+    }
+    log::warn!(
+        "Rejected {} {} from origin {:?}",
+        request.method(),
+        request.uri().path(),
+        origin
+    );
+    Err(StatusCode::FORBIDDEN)
+}
+
+#[derive(serde::Serialize)]
+struct ServerRoute {
+    method: &'static str,
+    path: &'static str,
+    access: RouteAccess,
+}
+
+#[derive(serde::Serialize)]
+struct ServerMiddleware {
+    name: &'static str,
+    // Effective parameters, e.g. {"origins": "*"}
+    parameters: std::collections::BTreeMap<&'static str, String>,
+}
+
+#[derive(serde::Serialize)]
+struct ServerDescription {
+    host: &'static str,
+    port: u16,
+    running: bool,
+    tls: bool,
+    // Only release builds start the server and serve the bundled frontend;
+    // debug builds use the frontend dev server
+    static_files: bool,
+    routes: Vec<ServerRoute>,
+    // Outermost first
+    middleware: Vec<ServerMiddleware>,
+    auth: bool,
+}
+
+/// The local HTTP server's effective configuration: address, routes and the
+/// middleware every request passes through. There is no TLS, authentication
+/// or rate limiting, so those are reported as off
+#[tauri::command]
+fn describe_server(server_status: State<StaticServerStatus>) -> ServerDescription {
+    let cors = ServerMiddleware {
+        name: "cors",
+        parameters: [
+            (
+                "origins",
+                "* (app origins only for app_origin routes)".to_string(),
+            ),
+            ("methods", "*".to_string()),
+            ("headers", "*".to_string()),
+        ]
+        .into_iter()
+        .collect(),
+    };
+    let body_limit = ServerMiddleware {
+        name: "body_limit",
+        parameters: [("json_bytes", SERVER_BODY_LIMIT.to_string())]
+            .into_iter()
+            .collect(),
+    };
+    let app_origin = ServerMiddleware {
+        name: "app_origin",
+        parameters: [("origins", app_origins().join(", "))]
+            .into_iter()
+            .collect(),
+    };
+    let middleware = vec![cors, body_limit, app_origin];
+
+    ServerDescription {
+        host: SERVER_HOST,
+        port: SERVER_PORT,
+        running: server_status.0.load(Ordering::SeqCst),
+        tls: false,
+        static_files: !cfg!(debug_assertions),
+        routes: SERVER_ROUTES
+            .iter()
+            .map(|&(method, path, access, _)| ServerRoute {
+                method,
+                path,
+                access,
+            })
+            .collect(),
+        // Reported from the middleware list so adding an auth layer there shows up here
+        auth: middleware.iter().any(|m| m.name == "auth"),
+        middleware,
+    }
+}
+
+#[cfg(not(debug_assertions))]
 fn start_static_server(app_handle: tauri::AppHandle) {
     let rt = tokio::runtime::Runtime::new().unwrap();
     rt.block_on(async {
-        const SERVER_PORT: u16 = 3838;
-        let url = format!("http://127.0.0.1:{}", SERVER_PORT);
+        let url = format!("http://{}:{}", SERVER_HOST, SERVER_PORT);
         let addr_str = url.replace("http://", "");
 
         let server_url_state = app_handle.state::<Mutex<ServerUrl>>();
@@ -285,52 +922,34 @@ fn start_static_server(app_handle: tauri::AppHandle) {
 
         log::info!("Serving static files from: {:?}", resource_path);
 
+        // Any site may call the public routes; app-only routes get no CORS
+        // headers for other origins, so their preflights fail
         let cors = CorsLayer::new()
-            .allow_origin(Any)
+            .allow_origin(AllowOrigin::predicate(|origin, parts| {
+                // A preflight asks on behalf of the method it names
+                let method = parts
+                    .headers
+                    .get(ACCESS_CONTROL_REQUEST_METHOD)
+                    .and_then(|method| Method::from_bytes(method.as_bytes()).ok())
+                    .unwrap_or_else(|| parts.method.clone());
+                origin_allowed(&method, parts.uri.path(), Some(origin))
+            }))
             .allow_methods(Any)
             .allow_headers(Any);
 
         let state = AppState {
             app_handle: app_handle.clone(),
-            http_client: Client::new(),
         };
 
-        let app = Router::new()
-            .route("/v1/*path", any(proxy_handler))
-            .route("/api/*path", any(proxy_handler))
-            .route("/ask", axum::routing::post(notifications::ask_handler))
-            .route(
-                "/ping",
-                axum::routing::get(|| async {
-                    log::info!("==== PING-PONG ====");
-                    "pong"
-                }),
-            )
-            .route(
-                "/message",
-                axum::routing::post(notifications::message_handler),
-            )
-            .route(
-                "/notification",
-                axum::routing::post(notifications::notification_handler),
-            )
-            .route("/overlay", axum::routing::post(overlay::overlay_handler))
-            .route("/click", axum::routing::post(controls::click_handler))
-            .route(
-                "/commands-stream",
-                axum::routing::get(commands::commands_stream_handler),
-            )
-            // Legacy HTTP endpoints (for backward compatibility during migration)
-            .route(
-                "/commands",
-                axum::routing::get(commands::get_commands_handler),
-            )
-            .route(
-                "/commands",
-                axum::routing::post(commands::post_commands_handler),
-            )
+        let mut app = Router::new();
+        for (_, path, _, handler) in SERVER_ROUTES {
+            app = app.route(path, handler());
+        }
+        let app = app
             .fallback_service(ServeDir::new(resource_path))
             .with_state(state)
+            .layer(axum::middleware::from_fn(require_app_origin))
+            .layer(DefaultBodyLimit::max(SERVER_BODY_LIMIT))
             .layer(cors);
 
         let listener = tokio::net::TcpListener::bind(&addr_str).await;
@@ -338,9 +957,12 @@ fn start_static_server(app_handle: tauri::AppHandle) {
         match listener {
             Ok(l) => {
                 log::info!("Web server listening on {}", url);
+                let server_status = app_handle.state::<StaticServerStatus>();
+                server_status.0.store(true, Ordering::SeqCst);
                 if let Err(e) = axum::serve(l, app.into_make_service()).await {
                     log::error!("Server error: {}", e);
                 }
+                server_status.0.store(false, Ordering::SeqCst);
             }
             Err(e) => {
                 log::error!(
@@ -365,42 +987,64 @@ pub fn run() {
     // Updater
     let builder = {
         builder = builder.plugin(tauri_plugin_updater::Builder::new().build());
-        builder.manage(Mutex::new(ServerUrl("".to_string())))
+        builder
+            .manage(Mutex::new(ServerUrl("".to_string())))
+            .manage(StaticServerStatus::default())
     };
 
     builder
         .setup(|app| {
+            app.manage(StartTime::now());
+
             // Load app config early so we can initialize everything with persisted values
             let loaded_config = shortcuts::load_config_from_disk(app.handle());
+            let loaded_start_hidden = loaded_config.start_hidden;
+            let proxy_idle_timeout_secs = loaded_config.proxy_idle_timeout_secs;
 
             // Initialize AppSettings with loaded ollama_url
             app.manage(AppSettings {
                 ollama_url: Mutex::new(loaded_config.ollama_url.clone()),
+                ollama_api_key: Mutex::new(loaded_config.ollama_api_key.clone()),
             });
 
             {
                 app.manage(OverlayState {
                     messages: Mutex::new(Vec::new()),
+                    position_saves: AtomicU64::new(0),
                 });
+                app.manage(overlay::PresentationModeState::default());
+                app.manage(controls::ControlsState::default());
+                app.manage(notifications::QuietHoursState::default());
+                app.manage(window_close::WindowCloseState::default());
 
                 app.manage({
                     let (tx, _rx) = broadcast::channel(100); // Buffer up to 100 commands
                     CommandState {
                         pending_commands: Mutex::new(std::collections::HashMap::new()),
                         command_broadcaster: tx,
+                        last_event_id: Mutex::new(0),
+                        broadcaster_paused: AtomicBool::new(false),
+                        held_commands: Mutex::new(std::collections::VecDeque::new()),
+                        history: Mutex::new(std::collections::VecDeque::new()),
                     }
                 });
             }
 
-            app.manage(UnifiedShortcutState {
-                config: Mutex::new(loaded_config),
-                registered_shortcuts: Mutex::new(Vec::new()),
-            });
+            app.manage(UnifiedShortcutState::new(loaded_config));
+
+            // Built after the config is managed so the User-Agent can be resolved
+            app.manage(HttpClientState::new(build_http_client(
+                &user_agent(app.handle()),
+                proxy_idle_timeout_secs,
+            )));
+
+            app.manage(tasks::BackgroundTasks::default());
+            app.manage(notifications::NotificationState::default());
 
             // We use the handle to call updater and restart
             {
                 let handle = app.handle().clone();
-                tauri::async_runtime::spawn(async move {
+                app.state::<tasks::BackgroundTasks>().spawn("update-check", async move {
                     // Notice we use the handle to get the updater
                     match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
                         handle.updater()
@@ -463,11 +1107,27 @@ pub fn run() {
                 });
             }
 
+            let log_stream = logging::LogStreamState::new();
             app.handle().plugin(
                 tauri_plugin_log::Builder::default()
-                    .level(log::LevelFilter::Info)
+                    .level(log::LevelFilter::Trace)
+                    .target(logging::stream_target(log_stream.sender.clone()))
                     .build(),
             )?;
+            app.manage(log_stream);
+            app.state::<tasks::BackgroundTasks>()
+                .spawn("log-events", logging::forward_log_events(app.handle().clone()));
+
+            app.manage(backend::BackendStatusState::default());
+            app.state::<tasks::BackgroundTasks>().spawn(
+                "backend-monitor",
+                backend::monitor_backend_status(app.handle().clone()),
+            );
+            log::set_max_level(logging::DEFAULT_LOG_LEVEL);
+            app.manage(logging::LogLevelState {
+                base_level: Mutex::new(logging::DEFAULT_LOG_LEVEL),
+                boost_generation: std::sync::atomic::AtomicU64::new(0),
+            });
 
             // HTTP server
             #[cfg(not(debug_assertions))]
@@ -500,6 +1160,7 @@ pub fn run() {
                     .on_menu_event(move |app, event| match event.id.as_ref() {
                         "quit" => {
                             log::info!("Exit called");
+                            app.state::<tasks::BackgroundTasks>().cancel_all();
                             app.exit(0);
                         }
                         "show" => {
@@ -513,44 +1174,65 @@ pub fn run() {
                     .build(app)?;
             }
 
+            // The launcher window is created hidden; show it unless configured to start in the tray
+            if loaded_start_hidden {
+                log::info!("start_hidden is set, keeping launcher window hidden");
+            } else if let Some(window) = app.get_webview_window("main") {
+                if let Err(e) = window.show() {
+                    log::warn!("Failed to show launcher window: {}", e);
+                }
+            }
+
+            let overlay_config = app
+                .state::<UnifiedShortcutState>()
+                .config
+                .lock()
+                .unwrap()
+                .clone();
+
             // Create the overlay window synchronously to avoid race conditions
-            match WebviewWindowBuilder::new(
-                app,
-                "overlay",
-                WebviewUrl::App("/overlay".into()),
-            )
-            .title("Observer Overlay")
-            .inner_size(700.0, 700.0)
-            .position(50.0, 50.0)
-            .decorations(false)
-            .transparent(true)
-            .always_on_top(true)
-            .skip_taskbar(true)
-            .visible(false)
-            .resizable(false)
-            .content_protected(true)
-            .build() {
-                Ok(window) => {
-                    log::info!("Overlay window created successfully with content protection");
-
-                    // Explicitly set content protection after window creation
-                    if let Err(e) = window.set_content_protected(true) {
-                        log::warn!("Could not set content protection on overlay window: {}", e);
-                    } else {
-                        log::info!("Content protection explicitly enabled on overlay window");
-                    }
+            if !overlay_config.overlay_enabled {
+                log::info!("Overlay disabled in config - not creating overlay window");
+            } else {
+                match overlay::ensure_overlay_window(app, &overlay_config) {
+                    Ok(window) => {
+                        // Never start on a monitor that is no longer connected
+                        if let Err(e) = overlay::snap_overlay_onscreen(&window) {
+                            log::warn!("Could not verify overlay placement: {}", e);
+                        }
 
-                    // Make the window draggable by setting it as focusable
-                    if let Err(e) = window.set_focus() {
-                        log::warn!("Could not focus overlay window: {}", e);
+                        // Anchored overlays follow resolution / DPI changes of their monitor
+                        overlay::reapply_overlay_anchor(app.handle());
+                        let anchor_handle = app.handle().clone();
+                        window.on_window_event(move |event| match event {
+                            tauri::WindowEvent::ScaleFactorChanged { .. } => {
+                                overlay::reapply_overlay_anchor(&anchor_handle);
+                            }
+                            tauri::WindowEvent::Moved(position) => {
+                                overlay::remember_overlay_position(&anchor_handle, *position);
+                            }
+                            _ => {}
+                        });
+                    }
+                    Err(e) => {
+                        log::error!("Failed to create overlay window: {}", e);
+                        // Don't panic, just log the error
                     }
-                }
-                Err(e) => {
-                    log::error!("Failed to create overlay window: {}", e);
-                    // Don't panic, just log the error
                 }
             }
 
+            // Started after the overlay exists so display changes can re-validate it
+            app.state::<tasks::BackgroundTasks>().spawn(
+                "displays",
+                overlay::monitor_displays(app.handle().clone()),
+            );
+
+            // Started after the overlay exists so quiet hours can hide it right away
+            app.state::<tasks::BackgroundTasks>().spawn(
+                "quiet-hours",
+                notifications::monitor_quiet_hours(app.handle().clone()),
+            );
+
             // Register shortcuts (config already loaded at app initialization)
             #[cfg(desktop)]
             {
@@ -565,27 +1247,442 @@ pub fn run() {
             Ok(())
         })
         .on_window_event(|window, event| {
-            match event {
-                tauri::WindowEvent::CloseRequested { api, .. } => {
-                    window.hide().unwrap();
-                    api.prevent_close();
-                }
-                _ => {}
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                api.prevent_close();
+                window_close::handle_close_requested(window);
             }
         })
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_os::init())
         .invoke_handler(tauri::generate_handler![
             get_server_url,
+            get_build_info,
+            get_uptime,
+            describe_server,
             set_ollama_url,
             get_ollama_url,
+            get_start_hidden,
+            set_start_hidden,
+            get_theme,
+            set_theme,
+            relaunch_app,
+            window_close::ack_window_close,
+            get_user_agent,
+            set_user_agent,
+            set_ollama_api_key,
+            get_ollama_api_key,
             check_ollama_servers,
             get_overlay_messages,
             clear_overlay_messages,
+            tasks::list_background_tasks,
+            tasks::cancel_background_task,
+            backend::measure_proxy_latency,
+            backend::preload_model,
+            backend::get_running_models,
+            backend::unload_model,
+            backend::profile_request,
+            backend::proxy_request,
+            backend::get_proxy_extra_headers,
+            backend::set_proxy_extra_header,
+            backend::get_backends_health,
+            backend::failover_to_next_backend,
+            backend::list_ollama_profiles,
+            backend::activate_ollama_profile,
+            backend::save_current_as_profile,
+            backend::get_proxy_pool_stats,
+            backend::set_proxy_keepalive,
+            logging::get_log_level,
+            logging::set_log_level,
+            logging::boost_log_level,
+            logging::get_log_event_level,
+            logging::set_log_event_level,
+            commands::get_agents,
+            commands::audit_agent_config,
+            commands::set_agent_name,
+            commands::get_command_history,
+            commands::replay_command,
+            commands::get_agent_activity,
+            commands::dispatch_command,
+            commands::pause_commands,
+            commands::resume_commands,
+            overlay::get_overlay_stats,
+            overlay::move_overlay,
+            overlay::resize_overlay,
+            overlay::get_overlay_skip_taskbar,
+            overlay::set_overlay_skip_taskbar,
+            overlay::get_overlay_resizable,
+            overlay::set_overlay_resizable,
+            overlay::get_overlay_accept_keyboard,
+            overlay::set_overlay_accept_keyboard,
+            overlay::get_overlay_anchor,
+            overlay::set_overlay_anchor,
+            overlay::save_overlay_layout,
+            overlay::apply_overlay_layout,
+            overlay::list_overlay_layouts,
+            overlay::set_agent_color,
+            overlay::set_agent_icon,
+            overlay::clear_overlay_messages_by_source,
+            overlay::seed_overlay_messages,
+            overlay::show_overlay_message,
+            overlay::set_overlay_presentation_mode,
+            overlay::resync_overlay,
+            overlay::is_overlay_content_protected,
             shortcuts::get_shortcut_config,
+            shortcuts::get_config_locked,
+            shortcuts::get_normalized_shortcut_config,
             shortcuts::get_registered_shortcuts,
+            shortcuts::get_shortcut_status,
+            shortcuts::get_shortcut_conflicts,
+            shortcuts::validate_shortcut,
+            shortcuts::get_shortcut_activity,
+            shortcuts::list_overlay_actions,
+            shortcuts::invoke_overlay_action,
+            shortcuts::rebind_shortcut,
+            shortcuts::apply_shortcut_changes,
+            shortcuts::reset_shortcuts_to_default,
+            shortcuts::remove_agent_shortcut,
+            shortcuts::set_agent_shortcut_condition,
+            shortcuts::unregister_all_shortcuts,
+            shortcuts::reregister_shortcuts,
+            shortcuts::export_shortcuts_cheatsheet,
+            shortcuts::preview_shortcut_config,
+            keymap::export_keymap,
+            keymap::import_keymap,
+            validation::validate_config,
+            config_diff::diff_config,
+            runtime_state::export_runtime_state,
+            runtime_state::import_runtime_state,
+            notifications::get_quiet_hours,
+            notifications::set_quiet_hours,
+            notifications::get_notification_capability,
+            notifications::request_notification_permission,
+            controls::perform_key,
+            controls::type_text,
+            controls::get_cursor_position,
+            controls::perform_scroll,
+            controls::get_active_window_title,
+            controls::get_controls_capabilities,
+            controls::set_controls_enabled,
             shortcuts::set_shortcut_config
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::Path;
+    use axum::http::Uri;
+    use axum::Json;
+    use futures::channel::mpsc;
+    use std::convert::Infallible;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    type ChunkSender = mpsc::UnboundedSender<Result<Bytes, Infallible>>;
+
+    // Reports back what the backend received
+    async fn echo(
+        method: Method,
+        headers: HeaderMap,
+        uri: Uri,
+        body: Bytes,
+    ) -> Json<serde_json::Value> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        Json(serde_json::json!({
+            "method": method.as_str(),
+            "path": uri.path(),
+            "query": uri.query(),
+            "authorization": header("authorization"),
+            "custom": header("x-custom"),
+            "body": String::from_utf8_lossy(&body),
+        }))
+    }
+
+    async fn status(Path(code): Path<u16>) -> Response {
+        Response::builder()
+            .status(code)
+            .header("x-mock", "status")
+            .body(Body::from(format!("status {}", code)))
+            .unwrap()
+    }
+
+    /// Starts a mock backend on an ephemeral port. Returns its base URL and the
+    /// sender feeding the chunks of its `/stream` response
+    async fn start_mock_backend() -> (String, ChunkSender) {
+        let (chunk_tx, chunk_rx) = mpsc::unbounded();
+        let chunk_rx = Arc::new(Mutex::new(Some(chunk_rx)));
+        let app = Router::new()
+            .route("/echo", any(echo))
+            .route("/status/:code", any(status))
+            .route(
+                "/slow",
+                any(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    "late"
+                }),
+            )
+            .route(
+                "/stream",
+                any(move || async move {
+                    let chunk_rx = chunk_rx.lock().unwrap().take();
+                    Body::from_stream(chunk_rx.expect("/stream requested twice"))
+                }),
+            );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}", addr), chunk_tx)
+    }
+
+    fn request(method: Method, uri: &str, headers: &[(&str, &str)], body: &str) -> Request<Bytes> {
+        let mut builder = Request::builder().method(method).uri(uri);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(Bytes::from(body.to_string())).unwrap()
+    }
+
+    async fn forward_with(
+        client: &Client,
+        base_url: &str,
+        api_key: Option<&str>,
+        request: Request<Bytes>,
+    ) -> Result<Response, StatusCode> {
+        forward_to_backend(client, "test", base_url, api_key, request).await
+    }
+
+    async fn forward(
+        base_url: &str,
+        api_key: Option<&str>,
+        request: Request<Bytes>,
+    ) -> Result<Response, StatusCode> {
+        forward_with(&Client::new(), base_url, api_key, request).await
+    }
+
+    async fn body_text(response: Response) -> String {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    async fn echoed(response: Response) -> serde_json::Value {
+        serde_json::from_str(&body_text(response).await).unwrap()
+    }
+
+    #[tokio::test]
+    async fn forwards_get_with_path_and_query() {
+        let (base_url, _) = start_mock_backend().await;
+
+        let response = forward(
+            &base_url,
+            None,
+            request(Method::GET, "/echo?name=value", &[], ""),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let echoed = echoed(response).await;
+        assert_eq!(echoed["method"], "GET");
+        assert_eq!(echoed["path"], "/echo");
+        assert_eq!(echoed["query"], "name=value");
+        assert_eq!(echoed["authorization"], serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn forwards_post_body_and_headers() {
+        let (base_url, _) = start_mock_backend().await;
+        let body = r#"{"model":"llama3","prompt":"hi"}"#;
+
+        let response = forward(
+            &base_url,
+            None,
+            request(Method::POST, "/echo", &[("x-custom", "kept")], body),
+        )
+        .await
+        .unwrap();
+
+        let echoed = echoed(response).await;
+        assert_eq!(echoed["method"], "POST");
+        assert_eq!(echoed["custom"], "kept");
+        assert_eq!(echoed["body"], body);
+    }
+
+    #[tokio::test]
+    async fn injects_api_key_without_caller_credentials() {
+        let (base_url, _) = start_mock_backend().await;
+
+        let response = forward(
+            &base_url,
+            Some("secret"),
+            request(Method::GET, "/echo", &[], ""),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(echoed(response).await["authorization"], "Bearer secret");
+    }
+
+    #[tokio::test]
+    async fn keeps_caller_authorization_over_api_key() {
+        let (base_url, _) = start_mock_backend().await;
+
+        let response = forward(
+            &base_url,
+            Some("secret"),
+            request(
+                Method::GET,
+                "/echo",
+                &[("authorization", "Bearer mine")],
+                "",
+            ),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(echoed(response).await["authorization"], "Bearer mine");
+    }
+
+    #[tokio::test]
+    async fn passes_upstream_error_status_through() {
+        let (base_url, _) = start_mock_backend().await;
+
+        for code in [404, 500, 503] {
+            let response = forward(
+                &base_url,
+                None,
+                request(Method::GET, &format!("/status/{}", code), &[], ""),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(response.status().as_u16(), code);
+            assert_eq!(response.headers()["x-mock"], "status");
+            assert_eq!(body_text(response).await, format!("status {}", code));
+        }
+    }
+
+    #[tokio::test]
+    async fn streams_response_before_backend_finishes() {
+        let (base_url, chunk_tx) = start_mock_backend().await;
+
+        chunk_tx.unbounded_send(Ok(Bytes::from("first"))).unwrap();
+        let response = forward(&base_url, None, request(Method::GET, "/stream", &[], ""))
+            .await
+            .unwrap();
+        let mut body = response.into_body();
+
+        // The backend is still open, so this chunk can only arrive by streaming
+        let frame = body.frame().await.unwrap().unwrap();
+        assert_eq!(frame.into_data().unwrap(), "first");
+
+        chunk_tx.unbounded_send(Ok(Bytes::from("second"))).unwrap();
+        drop(chunk_tx);
+        assert_eq!(body.collect().await.unwrap().to_bytes(), "second");
+    }
+
+    #[tokio::test]
+    async fn maps_timeout_to_bad_gateway() {
+        let (base_url, _) = start_mock_backend().await;
+        let client = Client::builder()
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+
+        let result = forward_with(
+            &client,
+            &base_url,
+            None,
+            request(Method::GET, "/slow", &[], ""),
+        )
+        .await;
+
+        assert_eq!(result.err(), Some(StatusCode::BAD_GATEWAY));
+    }
+
+    #[tokio::test]
+    async fn proxy_handler_forwards_with_configured_backend_state() {
+        let (base_url, _) = start_mock_backend().await;
+        let app = tauri::test::mock_builder()
+            .build(tauri::test::mock_context(tauri::test::noop_assets()))
+            .unwrap();
+        app.manage(AppSettings {
+            ollama_url: Mutex::new(Some(base_url)),
+            ollama_api_key: Mutex::new(Some("secret".to_string())),
+        });
+        let mut config = shortcuts::AppConfig::default();
+        config
+            .proxy_extra_headers
+            .insert("x-custom".to_string(), "from-config".to_string());
+        app.manage(UnifiedShortcutState::new(config));
+        app.manage(HttpClientState::new(Client::new()));
+
+        let state = AppState {
+            app_handle: app.handle().clone(),
+        };
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/echo?stream=false")
+            .body(Body::from("prompt"))
+            .unwrap();
+        let response = proxy_handler(AxumState(state), request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let echoed = echoed(response).await;
+        assert_eq!(echoed["method"], "POST");
+        assert_eq!(echoed["path"], "/echo");
+        assert_eq!(echoed["query"], "stream=false");
+        assert_eq!(echoed["authorization"], "Bearer secret");
+        assert_eq!(echoed["custom"], "from-config");
+        assert_eq!(echoed["body"], "prompt");
+    }
+
+    #[tokio::test]
+    async fn maps_unreachable_backend_to_bad_gateway() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let result = forward(&base_url, None, request(Method::GET, "/echo", &[], "")).await;
+
+        assert_eq!(result.err(), Some(StatusCode::BAD_GATEWAY));
+    }
+
+    #[test]
+    fn restricts_app_only_routes_to_app_origins() {
+        let foreign = HeaderValue::from_static("https://example.com");
+        let webview = HeaderValue::from_static("tauri://localhost");
+        let hosted = HeaderValue::from_static("http://127.0.0.1:3838");
+
+        assert!(!origin_allowed(&Method::POST, "/key", Some(&foreign)));
+        assert!(!origin_allowed(&Method::POST, "/click", Some(&foreign)));
+        assert!(!origin_allowed(&Method::POST, "/type", Some(&foreign)));
+        assert!(!origin_allowed(
+            &Method::GET,
+            "/logs/stream",
+            Some(&foreign)
+        ));
+        assert!(!origin_allowed(&Method::GET, "/overlay", Some(&foreign)));
+        assert!(origin_allowed(&Method::POST, "/key", Some(&webview)));
+        assert!(origin_allowed(&Method::POST, "/key", Some(&hosted)));
+        // Local processes send no Origin
+        assert!(origin_allowed(&Method::POST, "/key", None));
+    }
+
+    #[test]
+    fn leaves_public_routes_open_to_any_origin() {
+        let foreign = HeaderValue::from_static("https://example.com");
+
+        assert!(origin_allowed(&Method::POST, "/overlay", Some(&foreign)));
+        assert!(origin_allowed(&Method::GET, "/api/tags", Some(&foreign)));
+        // Only the listed method is restricted
+        assert!(origin_allowed(&Method::GET, "/key", Some(&foreign)));
+    }
+}
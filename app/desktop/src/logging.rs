@@ -0,0 +1,230 @@
+// In src-tauri/src/logging.rs
+
+use crate::AppState;
+use axum::{
+    extract::{Query, State as AxumState},
+    response::{sse::Event, Sse},
+};
+use futures::stream::Stream;
+use log::{Level, LevelFilter};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::broadcast;
+use tokio_stream::{wrappers::BroadcastStream, StreamExt};
+
+// The log plugin is built at Trace so that the effective level can be
+// adjusted at runtime through log::set_max_level alone.
+pub const DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::Info;
+
+pub struct LogLevelState {
+    // Level to return to once a boost expires
+    pub base_level: Mutex<LevelFilter>,
+    // Bumped on every boost so an older timer can't undo a newer boost
+    pub boost_generation: AtomicU64,
+}
+
+// Records from these crates are produced while delivering log lines
+// (SSE writes, event emits), so streaming them would feed back into itself
+const STREAM_EXCLUDED_TARGETS: [&str; 4] = ["hyper", "axum", "tauri", "tokio_util"];
+
+#[derive(Clone, Serialize, Debug)]
+pub struct LogLine {
+    level: String,
+    target: String,
+    message: String,
+    timestamp: u64,
+    #[serde(skip)]
+    severity: Level,
+}
+
+pub struct LogStreamState {
+    pub sender: broadcast::Sender<LogLine>,
+    // Minimum level forwarded as `log-line` events; Off until the frontend asks
+    pub event_level: Mutex<LevelFilter>,
+}
+
+impl LogStreamState {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(500);
+        Self {
+            sender,
+            event_level: Mutex::new(LevelFilter::Off),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct LogStreamQuery {
+    level: Option<String>,
+}
+
+/// Log plugin target that tees every record into the log stream channel
+pub fn stream_target(sender: broadcast::Sender<LogLine>) -> tauri_plugin_log::Target {
+    let dispatch = fern::Dispatch::new()
+        .filter(|metadata| {
+            !STREAM_EXCLUDED_TARGETS
+                .iter()
+                .any(|excluded| metadata.target().starts_with(excluded))
+        })
+        .chain(fern::Output::call(move |record| {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0);
+            // No receivers is the normal case, so send errors are ignored
+            let _ = sender.send(LogLine {
+                level: record.level().to_string(),
+                target: record.target().to_string(),
+                message: record.args().to_string(),
+                timestamp,
+                severity: record.level(),
+            });
+        }));
+
+    tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Dispatch(dispatch))
+}
+
+/// Forwards streamed log lines to the frontend as `log-line` events
+pub async fn forward_log_events(app_handle: AppHandle) {
+    let mut rx = app_handle.state::<LogStreamState>().sender.subscribe();
+
+    loop {
+        match rx.recv().await {
+            Ok(line) => {
+                let event_level = *app_handle
+                    .state::<LogStreamState>()
+                    .event_level
+                    .lock()
+                    .unwrap();
+                if line.severity <= event_level {
+                    // Not logged on failure: that would produce another line
+                    let _ = app_handle.emit("log-line", &line);
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+/// GET /logs/stream - server-sent events with one JSON log line per event
+pub async fn logs_stream_handler(
+    AxumState(state): AxumState<AppState>,
+    Query(query): Query<LogStreamQuery>,
+) -> Sse<impl Stream<Item = Result<Event, serde_json::Error>>> {
+    let min_level = query
+        .level
+        .as_deref()
+        .and_then(|level| level.parse::<LevelFilter>().ok())
+        .unwrap_or(LevelFilter::Trace);
+    log::info!(
+        "New SSE client connected to log stream (level: {})",
+        min_level
+    );
+
+    let rx = state
+        .app_handle
+        .state::<LogStreamState>()
+        .sender
+        .subscribe();
+
+    // Lagged receivers just skip the lines they missed
+    let stream = BroadcastStream::new(rx)
+        .filter_map(|result| result.ok())
+        .filter(move |line| line.severity <= min_level)
+        .map(|line| serde_json::to_string(&line).map(|json| Event::default().data(json)));
+
+    Sse::new(stream)
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level
+        .parse::<LevelFilter>()
+        .map_err(|_| format!("Invalid log level: {}", level))
+}
+
+#[tauri::command]
+pub async fn get_log_level() -> Result<String, String> {
+    Ok(log::max_level().to_string())
+}
+
+#[tauri::command]
+pub async fn set_log_level(
+    level: String,
+    log_state: State<'_, LogLevelState>,
+) -> Result<(), String> {
+    let level = parse_level(&level)?;
+    log::info!("Setting log level to {}", level);
+
+    *log_state.base_level.lock().unwrap() = level;
+    // Cancel any active boost so the new level sticks
+    log_state.boost_generation.fetch_add(1, Ordering::SeqCst);
+    log::set_max_level(level);
+
+    Ok(())
+}
+
+/// Raises the log level for `duration_secs`, then restores the base level
+#[tauri::command]
+pub async fn boost_log_level(
+    level: String,
+    duration_secs: u64,
+    log_state: State<'_, LogLevelState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let level = parse_level(&level)?;
+    let base_level = *log_state.base_level.lock().unwrap();
+    if level <= base_level {
+        return Err(format!(
+            "Boost level {} is not more verbose than the current level {}",
+            level, base_level
+        ));
+    }
+    let generation = log_state.boost_generation.fetch_add(1, Ordering::SeqCst) + 1;
+
+    log::set_max_level(level);
+    log::info!(
+        "Log level boosted to {} for {} seconds",
+        level,
+        duration_secs
+    );
+
+    let handle = app_handle.clone();
+    app_handle
+        .state::<crate::tasks::BackgroundTasks>()
+        .spawn("log-level-boost", async move {
+            tokio::time::sleep(std::time::Duration::from_secs(duration_secs)).await;
+
+            let log_state = handle.state::<LogLevelState>();
+            if log_state.boost_generation.load(Ordering::SeqCst) != generation {
+                // A newer boost or an explicit set_log_level took over
+                return;
+            }
+
+            let base_level = *log_state.base_level.lock().unwrap();
+            log::set_max_level(base_level);
+            log::info!("Log level boost expired, restored to {}", base_level);
+        });
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_log_event_level(log_stream: State<'_, LogStreamState>) -> Result<String, String> {
+    Ok(log_stream.event_level.lock().unwrap().to_string())
+}
+
+/// Sets the minimum level forwarded as `log-line` events ("off" disables them)
+#[tauri::command]
+pub async fn set_log_event_level(
+    level: String,
+    log_stream: State<'_, LogStreamState>,
+) -> Result<(), String> {
+    let level = parse_level(&level)?;
+    log::info!("Setting log event level to {}", level);
+    *log_stream.event_level.lock().unwrap() = level;
+    Ok(())
+}
@@ -4,9 +4,235 @@ use axum::{extract::State as AxumState, http::StatusCode, response::Json};
 use serde::{Deserialize, Serialize};
 use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 // ---- NEW IMPORT ----
+use crate::extract::JsonBody;
+use crate::shortcuts::{self, AppConfig, QuietHours, UnifiedShortcutState};
 use crate::AppState;
+use chrono::{Datelike, Local, NaiveTime, Timelike};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_notification::NotificationExt;
 
+// --- DE-DUPLICATION STATE ---
+#[derive(Default)]
+pub struct NotificationState {
+    // hash(title, body) -> (last fired, times suppressed since)
+    recent: Mutex<HashMap<u64, (Instant, u32)>>,
+}
+
+impl NotificationState {
+    /// Returns true if an identical notification fired within `window`,
+    /// bumping its suppressed count; otherwise records this one as fired
+    fn is_duplicate(&self, title: &str, body: &str, window: Duration) -> bool {
+        let mut hasher = DefaultHasher::new();
+        (title, body).hash(&mut hasher);
+        let key = hasher.finish();
+
+        let now = Instant::now();
+        let mut recent = self.recent.lock().unwrap();
+        recent.retain(|_, (fired_at, _)| now.duration_since(*fired_at) < window);
+
+        match recent.get_mut(&key) {
+            Some((_, suppressed)) => {
+                *suppressed += 1;
+                log::info!(
+                    "Suppressed duplicate notification '{}' ({} times in window)",
+                    title,
+                    suppressed
+                );
+                true
+            }
+            None => {
+                recent.insert(key, (now, 0));
+                false
+            }
+        }
+    }
+}
+
+// --- QUIET HOURS ---
+const QUIET_HOURS_POLL_INTERVAL: Duration = Duration::from_secs(30);
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+#[derive(Default)]
+pub struct QuietHoursState {
+    active: AtomicBool,
+    // Overlay visibility when quiet hours began, restored when they end
+    overlay_was_visible: AtomicBool,
+}
+
+fn parse_time(time: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(time, "%H:%M")
+        .map_err(|_| format!("Invalid time '{}', expected HH:MM", time))
+}
+
+/// Checks start/end times and weekday names
+pub fn validate_quiet_hours(quiet_hours: &QuietHours) -> Result<(), String> {
+    parse_time(&quiet_hours.start)?;
+    parse_time(&quiet_hours.end)?;
+    if let Some(day) = quiet_hours
+        .days
+        .iter()
+        .find(|day| !WEEKDAYS.contains(&day.as_str()))
+    {
+        return Err(format!("Invalid weekday: {}", day));
+    }
+    Ok(())
+}
+
+/// Whether the configured quiet hours cover the current local time
+pub fn is_quiet_now(config: &AppConfig) -> bool {
+    let Some(quiet_hours) = &config.quiet_hours else {
+        return false;
+    };
+    let (Ok(start), Ok(end)) = (parse_time(&quiet_hours.start), parse_time(&quiet_hours.end))
+    else {
+        return false;
+    };
+
+    let now = Local::now();
+    let time = NaiveTime::from_hms_opt(now.hour(), now.minute(), 0).unwrap_or(start);
+
+    // For windows spanning midnight, the early-morning part belongs to the previous day
+    let (in_window, starts_yesterday) = if start <= end {
+        (time >= start && time < end, false)
+    } else if time >= start {
+        (true, false)
+    } else {
+        (time < end, true)
+    };
+    if !in_window {
+        return false;
+    }
+
+    let mut weekday = now.weekday().num_days_from_monday() as usize;
+    if starts_yesterday {
+        weekday = (weekday + 6) % 7;
+    }
+    quiet_hours.days.is_empty() || quiet_hours.days.iter().any(|day| day == WEEKDAYS[weekday])
+}
+
+/// Background task: tracks entering/leaving quiet hours, emitting `quiet-hours-changed`
+/// and hiding / restoring the overlay when configured to
+pub async fn monitor_quiet_hours(app_handle: AppHandle) {
+    loop {
+        let config = app_handle
+            .state::<UnifiedShortcutState>()
+            .config
+            .lock()
+            .unwrap()
+            .clone();
+        let quiet = is_quiet_now(&config);
+        let quiet_state = app_handle.state::<QuietHoursState>();
+
+        if quiet_state.active.swap(quiet, Ordering::SeqCst) != quiet {
+            log::info!("{} quiet hours", if quiet { "Entering" } else { "Leaving" });
+            if let Err(e) = app_handle.emit("quiet-hours-changed", quiet) {
+                log::warn!("Failed to emit quiet-hours-changed event: {}", e);
+            }
+
+            let hide_overlay = config
+                .quiet_hours
+                .as_ref()
+                .is_some_and(|quiet_hours| quiet_hours.hide_overlay);
+            if let Some(window) = app_handle.get_webview_window(crate::overlay::OVERLAY_LABEL) {
+                if quiet && hide_overlay {
+                    let visible = window.is_visible().unwrap_or(false);
+                    quiet_state
+                        .overlay_was_visible
+                        .store(visible, Ordering::SeqCst);
+                    if let Err(e) = crate::overlay::set_overlay_visible(&app_handle, &window, false)
+                    {
+                        log::warn!("Failed to hide overlay for quiet hours: {}", e);
+                    }
+                } else if !quiet
+                    && quiet_state
+                        .overlay_was_visible
+                        .swap(false, Ordering::SeqCst)
+                {
+                    if let Err(e) = crate::overlay::set_overlay_visible(&app_handle, &window, true)
+                    {
+                        log::warn!("Failed to restore overlay after quiet hours: {}", e);
+                    }
+                }
+            }
+        }
+
+        tokio::time::sleep(QUIET_HOURS_POLL_INTERVAL).await;
+    }
+}
+
+#[tauri::command]
+pub async fn get_quiet_hours(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<Option<QuietHours>, String> {
+    Ok(shortcut_state.config.lock().unwrap().quiet_hours.clone())
+}
+
+/// Sets (or clears, when None) the quiet hours window (persisted)
+#[tauri::command]
+pub async fn set_quiet_hours(
+    quiet_hours: Option<QuietHours>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Setting quiet hours to {:?}", quiet_hours);
+    if let Some(quiet_hours) = &quiet_hours {
+        validate_quiet_hours(quiet_hours)?;
+    }
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        config.quiet_hours = quiet_hours;
+    })
+}
+
+// --- NOTIFICATION CAPABILITY ---
+#[derive(Serialize)]
+pub struct NotificationCapability {
+    // "granted", "denied", "prompt" or "prompt-with-rationale"
+    permission: String,
+    permitted: bool,
+    // False where the plugin cannot query the OS and always reports "granted"
+    // (desktop), so a blocked state there can't be detected
+    reliable: bool,
+}
+
+fn capability_from(state: tauri::plugin::PermissionState) -> NotificationCapability {
+    NotificationCapability {
+        permission: state.to_string(),
+        permitted: state == tauri::plugin::PermissionState::Granted,
+        reliable: cfg!(mobile),
+    }
+}
+
+#[tauri::command]
+pub async fn get_notification_capability(
+    app_handle: AppHandle,
+) -> Result<NotificationCapability, String> {
+    let state = app_handle
+        .notification()
+        .permission_state()
+        .map_err(|e| format!("Failed to read notification permission: {}", e))?;
+    Ok(capability_from(state))
+}
+
+/// Shows the OS notification permission prompt where the platform has one
+#[tauri::command]
+pub async fn request_notification_permission(
+    app_handle: AppHandle,
+) -> Result<NotificationCapability, String> {
+    log::info!("Requesting notification permission");
+    let state = app_handle
+        .notification()
+        .request_permission()
+        .map_err(|e| format!("Failed to request notification permission: {}", e))?;
+    Ok(capability_from(state))
+}
+
 // --- STRUCTS FOR /ask ---
 #[derive(Deserialize)]
 pub struct AskPayload {
@@ -36,7 +262,7 @@ pub struct NotificationPayload {
 // --- HANDLER for /ask (no changes) ---
 pub async fn ask_handler(
     AxumState(state): AxumState<AppState>,
-    Json(payload): Json<AskPayload>,
+    JsonBody(payload): JsonBody<AskPayload>,
 ) -> Result<Json<AskResponse>, StatusCode> {
     log::info!("V2: Received ask request: '{}'", payload.question);
 
@@ -60,7 +286,7 @@ pub async fn ask_handler(
 // ---- NEW HANDLER for /message ----
 pub async fn message_handler(
     AxumState(state): AxumState<AppState>,
-    Json(payload): Json<MessagePayload>,
+    JsonBody(payload): JsonBody<MessagePayload>,
 ) -> StatusCode {
     log::info!("V2: Received message request: '{}'", payload.message);
 
@@ -84,13 +310,36 @@ pub async fn message_handler(
 // ---- NEW HANDLER for /notification ----
 pub async fn notification_handler(
     AxumState(state): AxumState<AppState>,
-    Json(payload): Json<NotificationPayload>,
+    JsonBody(payload): JsonBody<NotificationPayload>,
 ) -> StatusCode {
     log::info!(
         "V2: Received system notification request: '{}'",
         payload.body
     );
 
+    let (dedupe_window_ms, quiet) = {
+        let shortcut_state = state.app_handle.state::<UnifiedShortcutState>();
+        let config = shortcut_state.config.lock().unwrap();
+        (config.notification_dedupe_window_ms, is_quiet_now(&config))
+    };
+    if quiet {
+        log::info!(
+            "Suppressed notification '{}' during quiet hours",
+            payload.title
+        );
+        return StatusCode::OK;
+    }
+    if let Some(window_ms) = dedupe_window_ms {
+        let notification_state = state.app_handle.state::<NotificationState>();
+        if notification_state.is_duplicate(
+            &payload.title,
+            &payload.body,
+            Duration::from_millis(window_ms),
+        ) {
+            return StatusCode::OK;
+        }
+    }
+
     // The .show() method for notifications is NON-BLOCKING.
     // It returns immediately, so we do NOT need spawn_blocking here.
     let builder = state
@@ -1,40 +1,241 @@
 // In src-tauri/src/overlay.rs
 
+use crate::extract::JsonBody;
+use crate::shortcuts::{self, AppConfig, OverlayLayout, OverlayPosition, UnifiedShortcutState};
 use crate::{AppState, OverlayMessage, OverlayState};
 use axum::{extract::State as AxumState, http::StatusCode, response::Json};
-use serde::Deserialize;
-use tauri::{Emitter, Manager};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use tauri::{
+    AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindow, WebviewWindowBuilder, Wry,
+};
+
+// Every overlay shortcut and command targets the single window with this label
+pub const OVERLAY_LABEL: &str = "overlay";
+
+// Largest move/resize delta accepted from commands
+const MAX_OVERLAY_DELTA: i32 = 10_000;
+// Bounds the overlay size is clamped to, in physical pixels
+const MIN_OVERLAY_SIZE: u32 = 200;
+const MAX_OVERLAY_SIZE: u32 = 10_000;
+
+/// Fails with "Overlay disabled" when the overlay is turned off in the config
+pub fn ensure_overlay_enabled(app_handle: &AppHandle) -> Result<(), String> {
+    let shortcut_state = app_handle.state::<UnifiedShortcutState>();
+    if shortcut_state.config.lock().unwrap().overlay_enabled {
+        Ok(())
+    } else {
+        Err("Overlay disabled".to_string())
+    }
+}
+
+/// Shows or hides the overlay and emits `overlay-visibility-changed`
+pub fn set_overlay_visible(
+    app_handle: &AppHandle,
+    window: &WebviewWindow,
+    visible: bool,
+) -> tauri::Result<()> {
+    if visible {
+        window.show()?;
+    } else {
+        window.hide()?;
+    }
+    if let Err(e) = app_handle.emit("overlay-visibility-changed", visible) {
+        log::warn!("Failed to emit overlay-visibility-changed event: {}", e);
+    }
+    Ok(())
+}
+
+/// With `overlay_hide_when_empty`, hides the overlay once its last message is gone
+fn hide_overlay_if_empty(app_handle: &AppHandle, remaining: usize) {
+    if remaining > 0 {
+        return;
+    }
+    let shortcut_state = app_handle.state::<UnifiedShortcutState>();
+    if !shortcut_state
+        .config
+        .lock()
+        .unwrap()
+        .overlay_hide_when_empty
+    {
+        return;
+    }
+    if let Some(window) = app_handle.get_webview_window(OVERLAY_LABEL) {
+        if window.is_visible().unwrap_or(false) {
+            log::info!("Hiding empty overlay");
+            if let Err(e) = set_overlay_visible(app_handle, &window, false) {
+                log::warn!("Failed to hide empty overlay: {}", e);
+            }
+        }
+    }
+}
 
 #[derive(Deserialize)]
 pub struct OverlayPayload {
     message: String,
+    agent_id: Option<String>,
+    // Explicit source tag; defaults to "agent:<id>" for agent messages
+    source: Option<String>,
+    // Removes this message after the given time instead of keeping it until cleared
+    duration_ms: Option<u64>,
+}
+
+// Builds a message, resolving the per-agent color/icon and the default source tag
+fn compose_overlay_message(
+    app_handle: &AppHandle,
+    content: String,
+    agent_id: Option<String>,
+    source: Option<String>,
+) -> OverlayMessage {
+    // Resolve the per-agent color/icon for tagged messages
+    let (color, icon) = match &agent_id {
+        Some(agent_id) => {
+            let shortcut_state = app_handle.state::<UnifiedShortcutState>();
+            let config = shortcut_state.config.lock().unwrap();
+            (
+                config.agent_colors.get(agent_id).cloned(),
+                config.agent_icons.get(agent_id).cloned(),
+            )
+        }
+        None => (None, None),
+    };
+
+    let source = source.or_else(|| {
+        agent_id
+            .as_ref()
+            .map(|agent_id| format!("agent:{}", agent_id))
+    });
+
+    let mut overlay_message = new_overlay_message(content, source);
+    overlay_message.agent_id = agent_id;
+    overlay_message.color = color;
+    overlay_message.icon = icon;
+    overlay_message
+}
+
+/// Removes the message with `message_id` once `duration_ms` has elapsed
+pub fn schedule_overlay_removal(app_handle: &AppHandle, message_id: String, duration_ms: u64) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(duration_ms)).await;
+
+        let overlay_state = app_handle.state::<OverlayState>();
+        let messages = {
+            let mut messages = overlay_state.messages.lock().unwrap();
+            let before = messages.len();
+            messages.retain(|m| m.id != message_id);
+            if messages.len() == before {
+                // Already cleared by the user or another subsystem
+                return;
+            }
+            messages.clone()
+        };
+
+        log::debug!("Overlay message {} expired", message_id);
+        if let Err(e) = app_handle.emit("overlay-messages-updated", &messages) {
+            log::warn!("Failed to emit overlay-messages-updated event: {}", e);
+        }
+        hide_overlay_if_empty(&app_handle, messages.len());
+    });
+}
+
+/// Shows a message on the overlay, optionally removing it after `duration_ms`;
+/// returns the message id
+#[tauri::command]
+pub async fn show_overlay_message(
+    message: String,
+    agent_id: Option<String>,
+    source: Option<String>,
+    duration_ms: Option<u64>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    ensure_overlay_enabled(&app_handle)?;
+
+    let overlay_message = compose_overlay_message(&app_handle, message, agent_id, source);
+    let message_id = overlay_message.id.clone();
+    push_overlay_message(&app_handle, overlay_message);
+
+    if let Some(duration_ms) = duration_ms {
+        schedule_overlay_removal(&app_handle, message_id.clone(), duration_ms);
+    }
+    Ok(message_id)
 }
 
 pub async fn overlay_handler(
     AxumState(state): AxumState<AppState>,
-    Json(payload): Json<OverlayPayload>,
-) -> StatusCode {
+    JsonBody(payload): JsonBody<OverlayPayload>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
     log::info!("Received overlay request: '{}'", payload.message);
 
-    // Get the overlay state from the app handle
+    if ensure_overlay_enabled(&state.app_handle).is_err() {
+        log::warn!("Rejected overlay request: overlay disabled");
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "error": "overlay_disabled" })),
+        ));
+    }
+
+    let overlay_message = compose_overlay_message(
+        &state.app_handle,
+        payload.message,
+        payload.agent_id,
+        payload.source,
+    );
+    let message_id = overlay_message.id.clone();
+    push_overlay_message(&state.app_handle, overlay_message);
+
+    if let Some(duration_ms) = payload.duration_ms {
+        schedule_overlay_removal(&state.app_handle, message_id, duration_ms);
+    }
+
+    Ok(StatusCode::OK)
+}
+
+/// Handler for GET /overlay
+/// Returns the messages currently on the HUD, oldest first
+pub async fn overlay_messages_handler(
+    AxumState(state): AxumState<AppState>,
+) -> Result<Json<Vec<OverlayMessage>>, (StatusCode, Json<serde_json::Value>)> {
+    if ensure_overlay_enabled(&state.app_handle).is_err() {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "error": "overlay_disabled" })),
+        ));
+    }
+
     let overlay_state = state.app_handle.state::<OverlayState>();
+    let messages = overlay_state.messages.lock().unwrap().clone();
+    Ok(Json(messages))
+}
 
-    // Create a new overlay message
-    let overlay_message = OverlayMessage {
+/// Builds a message with a fresh id and the current timestamp
+pub fn new_overlay_message(content: String, source: Option<String>) -> OverlayMessage {
+    OverlayMessage {
         id: uuid::Uuid::new_v4().to_string(),
-        content: payload.message,
+        content,
         timestamp: std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs(),
-    };
+        agent_id: None,
+        color: None,
+        icon: None,
+        source,
+    }
+}
+
+/// Adds a message to the overlay and notifies the frontend
+pub fn push_overlay_message(app_handle: &AppHandle, message: OverlayMessage) {
+    let overlay_state = app_handle.state::<OverlayState>();
 
     // Add the message to the overlay state
-    overlay_state.messages.lock().unwrap().push(overlay_message);
+    overlay_state.messages.lock().unwrap().push(message);
 
     // Emit event to notify frontend of message update
     let messages = overlay_state.messages.lock().unwrap().clone();
-    if let Err(e) = state.app_handle.emit("overlay-messages-updated", &messages) {
+    if let Err(e) = app_handle.emit("overlay-messages-updated", &messages) {
         log::warn!("Failed to emit overlay-messages-updated event: {}", e);
     } else {
         log::debug!(
@@ -42,6 +243,1301 @@ pub async fn overlay_handler(
             messages.len()
         );
     }
+}
+
+// Initial overlay geometry in logical pixels, also restored by the reset shortcut
+const OVERLAY_DEFAULT_SIZE: f64 = 700.0;
+const OVERLAY_DEFAULT_POSITION: f64 = 50.0;
+
+/// Puts the overlay back at its initial position and size, e.g. when it has
+/// drifted off a disconnected monitor
+pub fn reset_overlay_geometry(window: &WebviewWindow) -> Result<(), String> {
+    window
+        .set_size(tauri::Size::Logical(tauri::LogicalSize {
+            width: OVERLAY_DEFAULT_SIZE,
+            height: OVERLAY_DEFAULT_SIZE,
+        }))
+        .map_err(|e| format!("Failed to resize overlay: {}", e))?;
+    window
+        .set_position(tauri::Position::Logical(tauri::LogicalPosition {
+            x: OVERLAY_DEFAULT_POSITION,
+            y: OVERLAY_DEFAULT_POSITION,
+        }))
+        .map_err(|e| format!("Failed to move overlay: {}", e))
+}
+
+/// Where to create the overlay, in logical pixels: the saved position when it is
+/// on a connected monitor, otherwise the default offset on the primary monitor
+fn startup_overlay_position<M: Manager<Wry>>(manager: &M, config: &AppConfig) -> (f64, f64) {
+    let default = (OVERLAY_DEFAULT_POSITION, OVERLAY_DEFAULT_POSITION);
+    let Some(saved) = config.overlay_position else {
+        return default;
+    };
+    let app_handle = manager.app_handle();
+    let monitors = match app_handle.available_monitors() {
+        Ok(monitors) => monitors,
+        Err(e) => {
+            log::warn!(
+                "Failed to list monitors, not restoring overlay position: {}",
+                e
+            );
+            return default;
+        }
+    };
+
+    let on_monitor = monitors.iter().find(|monitor| {
+        let origin = monitor.position();
+        let size = monitor.size();
+        point_on_monitor(
+            saved.x,
+            saved.y,
+            (origin.x, origin.y),
+            (size.width, size.height),
+        )
+    });
+    if let Some(monitor) = on_monitor {
+        let scale = monitor.scale_factor();
+        return (saved.x as f64 / scale, saved.y as f64 / scale);
+    }
+
+    let primary = app_handle
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .or_else(|| monitors.into_iter().next());
+    let Some(primary) = primary else {
+        return default;
+    };
+    let origin = primary.position().to_logical::<f64>(primary.scale_factor());
+    let (x, y) = (
+        origin.x + OVERLAY_DEFAULT_POSITION,
+        origin.y + OVERLAY_DEFAULT_POSITION,
+    );
+    log::info!(
+        "Saved overlay position ({}, {}) is not on any connected monitor, repositioned to ({}, {}) on the primary monitor",
+        saved.x,
+        saved.y,
+        x,
+        y
+    );
+    (x, y)
+}
+
+// How long the overlay has to stay put before its position is written to
+// disk, so dragging doesn't rewrite settings.json on every frame
+const POSITION_SAVE_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Persists the overlay position once it has stopped moving, for the next launch
+pub fn remember_overlay_position(app_handle: &AppHandle, position: tauri::PhysicalPosition<i32>) {
+    let generation = app_handle
+        .state::<OverlayState>()
+        .position_saves
+        .fetch_add(1, Ordering::SeqCst)
+        + 1;
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(POSITION_SAVE_DELAY).await;
+        if app_handle
+            .state::<OverlayState>()
+            .position_saves
+            .load(Ordering::SeqCst)
+            != generation
+        {
+            // Moved again since, that move saves instead
+            return;
+        }
+
+        let position = OverlayPosition {
+            x: position.x,
+            y: position.y,
+        };
+        let shortcut_state = app_handle.state::<UnifiedShortcutState>();
+        if shortcut_state.config.lock().unwrap().overlay_position == Some(position) {
+            return;
+        }
+        if let Err(e) = shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+            config.overlay_position = Some(position);
+        }) {
+            log::debug!("Overlay position not saved: {}", e);
+        }
+    });
+}
+
+// Whether (x, y) lies on the monitor with the given physical origin and size
+fn point_on_monitor(x: i32, y: i32, origin: (i32, i32), size: (u32, u32)) -> bool {
+    x >= origin.0
+        && y >= origin.1
+        && (x as i64) < origin.0 as i64 + size.0 as i64
+        && (y as i64) < origin.1 as i64 + size.1 as i64
+}
+
+/// Returns the overlay window, creating it only if no window with the overlay label
+/// exists yet, so there is never more than one overlay for the shortcuts to act on
+pub fn ensure_overlay_window<M: Manager<Wry>>(
+    manager: &M,
+    config: &AppConfig,
+) -> tauri::Result<WebviewWindow> {
+    if let Some(window) = manager.get_webview_window(OVERLAY_LABEL) {
+        log::warn!("Attempted to create a duplicate overlay window, reusing the existing one");
+        return Ok(window);
+    }
+
+    let (start_x, start_y) = startup_overlay_position(manager, config);
+    let window =
+        WebviewWindowBuilder::new(manager, OVERLAY_LABEL, WebviewUrl::App("/overlay".into()))
+            .title("Observer Overlay")
+            .inner_size(OVERLAY_DEFAULT_SIZE, OVERLAY_DEFAULT_SIZE)
+            .position(start_x, start_y)
+            .decorations(false)
+            .transparent(true)
+            .always_on_top(true)
+            .skip_taskbar(config.overlay_skip_taskbar)
+            .visible(false)
+            .resizable(config.overlay_resizable)
+            .focusable(config.overlay_accept_keyboard)
+            .content_protected(true)
+            .build()?;
+
+    log::info!("Overlay window created successfully with content protection");
+
+    // Explicitly set content protection after window creation
+    if let Err(e) = window.set_content_protected(true) {
+        log::warn!("Could not set content protection on overlay window: {}", e);
+    } else {
+        log::info!("Content protection explicitly enabled on overlay window");
+    }
+
+    // Make the window draggable by setting it as focusable
+    if config.overlay_accept_keyboard {
+        if let Err(e) = window.set_focus() {
+            log::warn!("Could not focus overlay window: {}", e);
+        }
+    }
+
+    Ok(window)
+}
+
+// Each platform's query_content_protection only constructs some of the variants
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentProtection {
+    // The OS reports the overlay as excluded from capture
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    Active,
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    Inactive,
+    // Requested, but the OS offers no way to read it back
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    Unknown,
+    // The platform has no content protection at all
+    #[cfg_attr(any(target_os = "windows", target_os = "macos"), allow(dead_code))]
+    Unsupported,
+}
+
+#[cfg(target_os = "windows")]
+fn query_content_protection(window: &WebviewWindow) -> Result<ContentProtection, String> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{GetWindowDisplayAffinity, WDA_NONE};
+
+    let hwnd = window
+        .hwnd()
+        .map_err(|e| format!("Failed to get overlay window handle: {}", e))?;
+    let mut affinity = 0u32;
+    // SAFETY: hwnd is the live overlay window and affinity outlives the call
+    if unsafe { GetWindowDisplayAffinity(hwnd.0 as _, &mut affinity) } == 0 {
+        return Err("Failed to query overlay display affinity".to_string());
+    }
+    Ok(if affinity == WDA_NONE {
+        ContentProtection::Inactive
+    } else {
+        ContentProtection::Active
+    })
+}
+
+// macOS sets NSWindow.sharingType, which can't be read back here and is ignored
+// by some capture APIs on recent releases
+#[cfg(target_os = "macos")]
+fn query_content_protection(_window: &WebviewWindow) -> Result<ContentProtection, String> {
+    Ok(ContentProtection::Unknown)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn query_content_protection(_window: &WebviewWindow) -> Result<ContentProtection, String> {
+    Ok(ContentProtection::Unsupported)
+}
+
+/// Whether the overlay is actually hidden from screen capture, where the OS can
+/// tell; "unknown" and "unsupported" mean it can't be verified
+#[tauri::command]
+pub async fn is_overlay_content_protected(
+    app_handle: AppHandle,
+) -> Result<ContentProtection, String> {
+    ensure_overlay_enabled(&app_handle)?;
+    let window = app_handle
+        .get_webview_window(OVERLAY_LABEL)
+        .ok_or("Overlay window not found")?;
+    query_content_protection(&window)
+}
+
+// Offset from the monitor origin used when the overlay has to be repositioned
+const OVERLAY_DEFAULT_OFFSET: i32 = 50;
+
+/// Moves the overlay onto the primary monitor when its top-left corner is not on any
+/// connected monitor (e.g. after undocking). Returns whether the overlay was moved.
+pub fn snap_overlay_onscreen(window: &WebviewWindow) -> Result<bool, String> {
+    let position = window
+        .outer_position()
+        .map_err(|e| format!("Failed to get overlay position: {}", e))?;
+    let monitors = window
+        .available_monitors()
+        .map_err(|e| format!("Failed to list monitors: {}", e))?;
+
+    let on_screen = monitors.iter().any(|monitor| {
+        let origin = monitor.position();
+        let size = monitor.size();
+        point_on_monitor(
+            position.x,
+            position.y,
+            (origin.x, origin.y),
+            (size.width, size.height),
+        )
+    });
+    if on_screen {
+        return Ok(false);
+    }
+
+    let primary = window
+        .primary_monitor()
+        .map_err(|e| format!("Failed to get primary monitor: {}", e))?
+        .or_else(|| monitors.into_iter().next())
+        .ok_or("No monitor available to place the overlay on")?;
+    let origin = primary.position();
+    let new_x = origin.x.saturating_add(OVERLAY_DEFAULT_OFFSET);
+    let new_y = origin.y.saturating_add(OVERLAY_DEFAULT_OFFSET);
+
+    window
+        .set_position(tauri::Position::Physical(tauri::PhysicalPosition {
+            x: new_x,
+            y: new_y,
+        }))
+        .map_err(|e| format!("Failed to move overlay: {}", e))?;
+
+    log::info!(
+        "Overlay at ({}, {}) was not on any connected monitor, repositioned to ({}, {})",
+        position.x,
+        position.y,
+        new_x,
+        new_y
+    );
+    Ok(true)
+}
+
+pub const OVERLAY_ANCHORS: [&str; 8] = [
+    "top-left",
+    "top",
+    "top-right",
+    "left",
+    "right",
+    "bottom-left",
+    "bottom",
+    "bottom-right",
+];
+// Gap kept between an anchored overlay and the work area edge
+const ANCHOR_MARGIN: i64 = 20;
+
+/// Positions the overlay at `anchor` within the work area of its current monitor
+pub fn apply_overlay_anchor(window: &WebviewWindow, anchor: &str) -> Result<(i32, i32), String> {
+    if !OVERLAY_ANCHORS.contains(&anchor) {
+        return Err(format!("Unknown overlay anchor: {}", anchor));
+    }
+
+    let monitor = match window
+        .current_monitor()
+        .map_err(|e| format!("Failed to get current monitor: {}", e))?
+    {
+        Some(monitor) => monitor,
+        None => window
+            .primary_monitor()
+            .map_err(|e| format!("Failed to get primary monitor: {}", e))?
+            .ok_or("No monitor available to anchor the overlay to")?,
+    };
+    let area = monitor.work_area();
+    let size = window
+        .outer_size()
+        .map_err(|e| format!("Failed to get overlay size: {}", e))?;
+
+    // Start, centered or end placement along one axis
+    let place = |origin: i32, extent: u32, length: u32, start: bool, end: bool| -> i64 {
+        let (origin, extent, length) = (origin as i64, extent as i64, length as i64);
+        if start {
+            origin + ANCHOR_MARGIN
+        } else if end {
+            origin + extent - length - ANCHOR_MARGIN
+        } else {
+            origin + (extent - length) / 2
+        }
+    };
+    let x = place(
+        area.position.x,
+        area.size.width,
+        size.width,
+        anchor.ends_with("left"),
+        anchor.ends_with("right"),
+    );
+    let y = place(
+        area.position.y,
+        area.size.height,
+        size.height,
+        anchor.starts_with("top"),
+        anchor.starts_with("bottom"),
+    );
+    let (x, y) = (
+        x.clamp(i32::MIN as i64, i32::MAX as i64) as i32,
+        y.clamp(i32::MIN as i64, i32::MAX as i64) as i32,
+    );
+
+    window
+        .set_position(tauri::Position::Physical(tauri::PhysicalPosition { x, y }))
+        .map_err(|e| format!("Failed to move overlay: {}", e))?;
+
+    log::info!("Overlay anchored {} at ({}, {})", anchor, x, y);
+    Ok((x, y))
+}
+
+/// Centers the overlay on its current monitor (the primary one when that can't
+/// be determined) and returns the new position
+pub fn center_overlay(window: &WebviewWindow) -> Result<(i32, i32), String> {
+    let monitor = match window
+        .current_monitor()
+        .map_err(|e| format!("Failed to get current monitor: {}", e))?
+    {
+        Some(monitor) => monitor,
+        None => window
+            .primary_monitor()
+            .map_err(|e| format!("Failed to get primary monitor: {}", e))?
+            .ok_or("No monitor available to center the overlay on")?,
+    };
+    let origin = monitor.position();
+    let area = monitor.size();
+    let size = window
+        .inner_size()
+        .map_err(|e| format!("Failed to get overlay size: {}", e))?;
+
+    let center = |origin: i32, extent: u32, length: u32| -> i32 {
+        (origin as i64 + (extent as i64 - length as i64) / 2)
+            .clamp(i32::MIN as i64, i32::MAX as i64) as i32
+    };
+    let x = center(origin.x, area.width, size.width);
+    let y = center(origin.y, area.height, size.height);
+
+    window
+        .set_position(tauri::Position::Physical(tauri::PhysicalPosition { x, y }))
+        .map_err(|e| format!("Failed to move overlay: {}", e))?;
+
+    Ok((x, y))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SnapEdge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl SnapEdge {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SnapEdge::Top => "top",
+            SnapEdge::Bottom => "bottom",
+            SnapEdge::Left => "left",
+            SnapEdge::Right => "right",
+        }
+    }
+}
+
+/// Moves the overlay flush against `edge` of its current monitor's work area,
+/// keeping the other coordinate, and returns the new position. Monitor
+/// positions are absolute, so this stays on the right display in multi-monitor setups
+pub fn snap_overlay_to_edge(window: &WebviewWindow, edge: SnapEdge) -> Result<(i32, i32), String> {
+    let monitor = match window
+        .current_monitor()
+        .map_err(|e| format!("Failed to get current monitor: {}", e))?
+    {
+        Some(monitor) => monitor,
+        None => window
+            .primary_monitor()
+            .map_err(|e| format!("Failed to get primary monitor: {}", e))?
+            .ok_or("No monitor available to snap the overlay to")?,
+    };
+    let area = monitor.work_area();
+    let position = window
+        .outer_position()
+        .map_err(|e| format!("Failed to get overlay position: {}", e))?;
+    let size = window
+        .inner_size()
+        .map_err(|e| format!("Failed to get overlay size: {}", e))?;
+
+    let far_edge = |origin: i32, extent: u32, length: u32| -> i32 {
+        (origin as i64 + extent as i64 - length as i64).clamp(i32::MIN as i64, i32::MAX as i64)
+            as i32
+    };
+    let (x, y) = match edge {
+        SnapEdge::Top => (position.x, area.position.y),
+        SnapEdge::Bottom => (
+            position.x,
+            far_edge(area.position.y, area.size.height, size.height),
+        ),
+        SnapEdge::Left => (area.position.x, position.y),
+        SnapEdge::Right => (
+            far_edge(area.position.x, area.size.width, size.width),
+            position.y,
+        ),
+    };
+
+    window
+        .set_position(tauri::Position::Physical(tauri::PhysicalPosition { x, y }))
+        .map_err(|e| format!("Failed to move overlay: {}", e))?;
+
+    Ok((x, y))
+}
+
+/// Re-applies the configured anchor, if any (used when the display setup changes)
+pub fn reapply_overlay_anchor(app_handle: &AppHandle) {
+    let anchor = app_handle
+        .state::<UnifiedShortcutState>()
+        .config
+        .lock()
+        .unwrap()
+        .overlay_anchor
+        .clone();
+    if let (Some(anchor), Some(window)) = (anchor, app_handle.get_webview_window(OVERLAY_LABEL)) {
+        if let Err(e) = apply_overlay_anchor(&window, &anchor) {
+            log::warn!("Failed to re-anchor overlay: {}", e);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_overlay_anchor(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<Option<String>, String> {
+    Ok(shortcut_state.config.lock().unwrap().overlay_anchor.clone())
+}
+
+/// Pins the overlay to a screen corner/edge (persisted); None returns to absolute positioning
+#[tauri::command]
+pub async fn set_overlay_anchor(
+    anchor: Option<String>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Setting overlay anchor to {:?}", anchor);
+    shortcuts::ensure_config_unlocked(&shortcut_state)?;
+    ensure_overlay_enabled(&app_handle)?;
+
+    if let Some(anchor) = &anchor {
+        let window = app_handle
+            .get_webview_window(OVERLAY_LABEL)
+            .ok_or("Overlay window not found")?;
+        apply_overlay_anchor(&window, anchor)?;
+        ensure_overlay_click_through(&window);
+    }
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        config.overlay_anchor = anchor;
+    })
+}
+
+// Tauri has no display-change event, so the monitor list is polled
+const DISPLAY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+struct DisplayInfo {
+    name: Option<String>,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    scale_factor: f64,
+}
+
+fn current_displays(app_handle: &AppHandle) -> Result<Vec<DisplayInfo>, String> {
+    let monitors = app_handle
+        .available_monitors()
+        .map_err(|e| format!("Failed to list monitors: {}", e))?;
+    Ok(monitors
+        .iter()
+        .map(|monitor| DisplayInfo {
+            name: monitor.name().cloned(),
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+            scale_factor: monitor.scale_factor(),
+        })
+        .collect())
+}
+
+// Keeps the overlay on a connected monitor at its anchor, and optionally
+// re-registers the shortcuts, after the display setup changed
+fn on_displays_changed(app_handle: &AppHandle, displays: &[DisplayInfo]) {
+    log::info!("Display setup changed: {} monitor(s)", displays.len());
+
+    if let Some(window) = app_handle.get_webview_window(OVERLAY_LABEL) {
+        if let Err(e) = snap_overlay_onscreen(&window) {
+            log::warn!("Failed to snap overlay on-screen: {}", e);
+        }
+        reapply_overlay_anchor(app_handle);
+    }
+
+    let reregister = app_handle
+        .state::<UnifiedShortcutState>()
+        .config
+        .lock()
+        .unwrap()
+        .reregister_shortcuts_on_display_change;
+    if reregister {
+        if let Err(e) = shortcuts::refresh_shortcuts(app_handle) {
+            log::warn!(
+                "Failed to re-register shortcuts after display change: {}",
+                e
+            );
+        }
+    }
+
+    if let Err(e) = app_handle.emit("displays-changed", displays) {
+        log::warn!("Failed to emit displays-changed event: {}", e);
+    }
+}
+
+/// Background task: watches the connected monitors and emits `displays-changed`
+/// (after re-validating the overlay) whenever they change
+pub async fn monitor_displays(app_handle: AppHandle) {
+    let mut known = current_displays(&app_handle).ok();
+    loop {
+        tokio::time::sleep(DISPLAY_POLL_INTERVAL).await;
+
+        let displays = match current_displays(&app_handle) {
+            Ok(displays) => displays,
+            Err(e) => {
+                log::warn!("{}", e);
+                continue;
+            }
+        };
+        if known.as_ref() != Some(&displays) {
+            // The first successful poll only establishes the baseline
+            if known.is_some() {
+                on_displays_changed(&app_handle, &displays);
+            }
+            known = Some(displays);
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+struct OverlayLayoutApplied {
+    name: String,
+    layout: OverlayLayout,
+}
+
+/// Saves the overlay's current position, size and anchor as a named layout,
+/// replacing any layout with the same name. The frontend passes its opacity
+#[tauri::command]
+pub async fn save_overlay_layout(
+    name: String,
+    opacity: Option<f64>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<OverlayLayout, String> {
+    ensure_overlay_enabled(&app_handle)?;
+    if name.trim().is_empty() {
+        return Err("Layout name must not be empty".to_string());
+    }
+    if let Some(opacity) = opacity {
+        if !(0.0..=1.0).contains(&opacity) {
+            return Err("opacity must be between 0.0 and 1.0".to_string());
+        }
+    }
+
+    let window = app_handle
+        .get_webview_window(OVERLAY_LABEL)
+        .ok_or("Overlay window not found")?;
+    let position = window
+        .outer_position()
+        .map_err(|e| format!("Failed to get overlay position: {}", e))?;
+    let size = window
+        .inner_size()
+        .map_err(|e| format!("Failed to get overlay size: {}", e))?;
+
+    let layout = OverlayLayout {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        anchor: shortcut_state.config.lock().unwrap().overlay_anchor.clone(),
+        opacity,
+    };
+
+    log::info!("Saving overlay layout '{}': {:?}", name, layout);
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        config.overlay_layouts.insert(name, layout.clone());
+    })?;
+
+    Ok(layout)
+}
+
+/// Restores a saved layout: size first, then the anchor (persisted) or the
+/// absolute position. Emits `overlay-layout-applied` so the frontend can apply
+/// the opacity
+#[tauri::command]
+pub async fn apply_overlay_layout(
+    name: String,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<OverlayLayout, String> {
+    // Checked before the window changes so a locked config leaves it untouched
+    shortcuts::ensure_config_unlocked(&shortcut_state)?;
+    ensure_overlay_enabled(&app_handle)?;
+
+    let layout = shortcut_state
+        .config
+        .lock()
+        .unwrap()
+        .overlay_layouts
+        .get(&name)
+        .cloned()
+        .ok_or_else(|| format!("Unknown overlay layout: {}", name))?;
+    let window = app_handle
+        .get_webview_window(OVERLAY_LABEL)
+        .ok_or("Overlay window not found")?;
+
+    window
+        .set_size(tauri::Size::Physical(tauri::PhysicalSize {
+            width: layout.width.clamp(MIN_OVERLAY_SIZE, MAX_OVERLAY_SIZE),
+            height: layout.height.clamp(MIN_OVERLAY_SIZE, MAX_OVERLAY_SIZE),
+        }))
+        .map_err(|e| format!("Failed to resize overlay: {}", e))?;
+    match &layout.anchor {
+        Some(anchor) => {
+            apply_overlay_anchor(&window, anchor)?;
+        }
+        None => window
+            .set_position(tauri::Position::Physical(tauri::PhysicalPosition {
+                x: layout.x,
+                y: layout.y,
+            }))
+            .map_err(|e| format!("Failed to move overlay: {}", e))?,
+    }
+    ensure_overlay_click_through(&window);
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        config.overlay_anchor = layout.anchor.clone();
+    })?;
+
+    log::info!("Applied overlay layout '{}'", name);
+    let event = OverlayLayoutApplied {
+        name,
+        layout: layout.clone(),
+    };
+    if let Err(e) = app_handle.emit("overlay-layout-applied", &event) {
+        log::warn!("Failed to emit overlay-layout-applied event: {}", e);
+    }
+
+    Ok(layout)
+}
+
+/// Saved overlay layouts by name
+#[tauri::command]
+pub async fn list_overlay_layouts(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<HashMap<String, OverlayLayout>, String> {
+    Ok(shortcut_state
+        .config
+        .lock()
+        .unwrap()
+        .overlay_layouts
+        .clone())
+}
+
+/// Clears all overlay messages and notifies the frontend
+/// (shared by the clear command and the clear shortcut)
+pub fn clear_overlay(app_handle: &AppHandle, overlay_state: &OverlayState) {
+    log::info!("Clearing overlay messages");
+    overlay_state.messages.lock().unwrap().clear();
+
+    // Emit event to notify frontend of cleared messages
+    let empty_messages: Vec<OverlayMessage> = vec![];
+    if let Err(e) = app_handle.emit("overlay-messages-updated", &empty_messages) {
+        log::warn!(
+            "Failed to emit overlay-messages-updated event after clear: {}",
+            e
+        );
+    } else {
+        log::debug!("Emitted overlay-messages-updated event with 0 messages after clear");
+    }
+    hide_overlay_if_empty(app_handle, 0);
+}
+
+/// Removes only the messages tagged with `source`, returning how many were removed
+#[tauri::command]
+pub async fn clear_overlay_messages_by_source(
+    source: String,
+    overlay_state: State<'_, OverlayState>,
+    app_handle: AppHandle,
+) -> Result<usize, String> {
+    ensure_overlay_enabled(&app_handle)?;
+    log::info!("Clearing overlay messages from source '{}'", source);
+
+    let (messages, removed) = {
+        let mut messages = overlay_state.messages.lock().unwrap();
+        let before = messages.len();
+        messages.retain(|m| m.source.as_deref() != Some(source.as_str()));
+        let removed = before - messages.len();
+        (messages.clone(), removed)
+    };
+
+    if let Err(e) = app_handle.emit("overlay-messages-updated", &messages) {
+        log::warn!("Failed to emit overlay-messages-updated event: {}", e);
+    }
+    hide_overlay_if_empty(&app_handle, messages.len());
+
+    Ok(removed)
+}
+
+// Source tag on seeded messages, so clear_overlay_messages_by_source can drop them
+const SEED_SOURCE: &str = "seed";
+const MAX_SEED_MESSAGES: usize = 200;
+
+// Sample content of increasing length, to exercise wrapping and truncation
+const SEED_CONTENTS: [&str; 4] = [
+    "Done.",
+    "Detected a new message in the team channel.",
+    "The build finished with 3 warnings. The slowest step was dependency resolution, which took 42 seconds.",
+    "Summary of the last hour: two meetings, one pull request review and a long debugging session on the overlay renderer. Nothing needs your attention right now, but the flaky test in the settings page failed again and may be worth a look later today.",
+];
+const SEED_LEVELS: [&str; 3] = ["info", "warning", "error"];
+
+/// Debug builds only: pushes `count` varied sample messages (lengths, agents,
+/// and with `include_levels` a level prefix) for overlay UI work. Messages have
+/// no structured level, so levels are rendered into the content
+#[tauri::command]
+pub async fn seed_overlay_messages(
+    count: usize,
+    include_levels: Option<bool>,
+    overlay_state: State<'_, OverlayState>,
+    app_handle: AppHandle,
+) -> Result<usize, String> {
+    if !cfg!(debug_assertions) {
+        return Err("Seeding overlay messages is only available in debug builds".to_string());
+    }
+    ensure_overlay_enabled(&app_handle)?;
+    if count == 0 || count > MAX_SEED_MESSAGES {
+        return Err(format!("count must be between 1 and {}", MAX_SEED_MESSAGES));
+    }
+
+    log::info!("Seeding {} sample overlay messages", count);
+
+    let include_levels = include_levels.unwrap_or(false);
+    let seeded: Vec<OverlayMessage> = (0..count)
+        .map(|i| {
+            let mut content = SEED_CONTENTS[i % SEED_CONTENTS.len()].to_string();
+            if include_levels {
+                content = format!("[{}] {}", SEED_LEVELS[i % SEED_LEVELS.len()], content);
+            }
+            // Every third message is untagged, the rest alternate between two agents
+            let agent_id = match i % 3 {
+                0 => None,
+                n => Some(format!("sample-agent-{}", n)),
+            };
+            compose_overlay_message(
+                &app_handle,
+                content,
+                agent_id,
+                Some(SEED_SOURCE.to_string()),
+            )
+        })
+        .collect();
+
+    let messages = {
+        let mut messages = overlay_state.messages.lock().unwrap();
+        messages.extend(seeded);
+        messages.clone()
+    };
+    if let Err(e) = app_handle.emit("overlay-messages-updated", &messages) {
+        log::warn!("Failed to emit overlay-messages-updated event: {}", e);
+    }
+
+    Ok(count)
+}
+
+#[tauri::command]
+pub async fn get_overlay_skip_taskbar(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<bool, String> {
+    Ok(shortcut_state.config.lock().unwrap().overlay_skip_taskbar)
+}
+
+/// Controls whether the overlay is hidden from the taskbar / alt-tab list (persisted)
+#[tauri::command]
+pub async fn set_overlay_skip_taskbar(
+    enabled: bool,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Setting overlay skip_taskbar to {}", enabled);
+    shortcuts::ensure_config_unlocked(&shortcut_state)?;
+    ensure_overlay_enabled(&app_handle)?;
+
+    let window = app_handle
+        .get_webview_window(OVERLAY_LABEL)
+        .ok_or("Overlay window not found")?;
+    window
+        .set_skip_taskbar(enabled)
+        .map_err(|e| format!("Failed to set overlay skip_taskbar: {}", e))?;
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        config.overlay_skip_taskbar = enabled;
+    })
+}
+
+#[tauri::command]
+pub async fn get_overlay_resizable(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<bool, String> {
+    Ok(shortcut_state.config.lock().unwrap().overlay_resizable)
+}
+
+/// Controls whether the overlay can be resized with the mouse (persisted)
+#[tauri::command]
+pub async fn set_overlay_resizable(
+    enabled: bool,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Setting overlay resizable to {}", enabled);
+    shortcuts::ensure_config_unlocked(&shortcut_state)?;
+    ensure_overlay_enabled(&app_handle)?;
+
+    let window = app_handle
+        .get_webview_window(OVERLAY_LABEL)
+        .ok_or("Overlay window not found")?;
+    window
+        .set_resizable(enabled)
+        .map_err(|e| format!("Failed to set overlay resizable: {}", e))?;
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        config.overlay_resizable = enabled;
+    })
+}
+
+#[tauri::command]
+pub async fn get_overlay_accept_keyboard(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<bool, String> {
+    Ok(shortcut_state
+        .config
+        .lock()
+        .unwrap()
+        .overlay_accept_keyboard)
+}
+
+/// Controls whether the overlay can take keyboard focus (persisted). Mouse
+/// click-through is unaffected, so the two input paths are set independently.
+/// On macOS an overlay that already has focus keeps it until it loses it once
+#[tauri::command]
+pub async fn set_overlay_accept_keyboard(
+    enabled: bool,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Setting overlay keyboard input to {}", enabled);
+    shortcuts::ensure_config_unlocked(&shortcut_state)?;
+    ensure_overlay_enabled(&app_handle)?;
+
+    let window = app_handle
+        .get_webview_window(OVERLAY_LABEL)
+        .ok_or("Overlay window not found")?;
+    window
+        .set_focusable(enabled)
+        .map_err(|e| format!("Failed to set overlay focusable: {}", e))?;
+    ensure_overlay_click_through(&window);
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| {
+        config.overlay_accept_keyboard = enabled;
+    })
+}
+
+#[derive(Serialize)]
+pub struct OverlayStats {
+    message_count: usize,
+    oldest_timestamp: Option<u64>,
+    newest_timestamp: Option<u64>,
+    // Rough footprint: sum of the message content lengths in bytes
+    approx_content_bytes: usize,
+}
+
+#[tauri::command]
+pub async fn get_overlay_stats(
+    overlay_state: State<'_, OverlayState>,
+    app_handle: AppHandle,
+) -> Result<OverlayStats, String> {
+    ensure_overlay_enabled(&app_handle)?;
+    let messages = overlay_state.messages.lock().unwrap();
+
+    Ok(OverlayStats {
+        message_count: messages.len(),
+        oldest_timestamp: messages.iter().map(|m| m.timestamp).min(),
+        newest_timestamp: messages.iter().map(|m| m.timestamp).max(),
+        approx_content_bytes: messages.iter().map(|m| m.content.len()).sum(),
+    })
+}
+
+#[derive(Serialize, Clone)]
+pub struct OverlaySync {
+    message_count: usize,
+    visible: bool,
+    theme: Option<String>,
+}
+
+/// Re-emits the authoritative overlay state so a reloaded or drifted frontend
+/// can rebuild: the full message list, then an `overlay-resynced` event with
+/// visibility and theme
+#[tauri::command]
+pub async fn resync_overlay(
+    overlay_state: State<'_, OverlayState>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<OverlaySync, String> {
+    log::info!("Resyncing overlay state");
+    ensure_overlay_enabled(&app_handle)?;
+
+    let window = app_handle
+        .get_webview_window(OVERLAY_LABEL)
+        .ok_or("Overlay window not found")?;
+    let messages = overlay_state.messages.lock().unwrap().clone();
+    let sync = OverlaySync {
+        message_count: messages.len(),
+        visible: window.is_visible().unwrap_or(false),
+        theme: shortcut_state.config.lock().unwrap().theme.clone(),
+    };
+
+    app_handle
+        .emit("overlay-messages-updated", &messages)
+        .map_err(|e| format!("Failed to emit overlay-messages-updated event: {}", e))?;
+    if let Err(e) = app_handle.emit("overlay-resynced", &sync) {
+        log::warn!("Failed to emit overlay-resynced event: {}", e);
+    }
+
+    Ok(sync)
+}
+
+// Helper function to ensure overlay always ignores cursor events
+pub fn ensure_overlay_click_through(window: &WebviewWindow) {
+    if let Err(e) = window.set_ignore_cursor_events(true) {
+        log::warn!("Failed to re-enable click-through on overlay: {}", e);
+    }
+}
+
+fn validate_delta(dx: i32, dy: i32) -> Result<(), String> {
+    // unsigned_abs so that i32::MIN can't overflow
+    let max = MAX_OVERLAY_DELTA.unsigned_abs();
+    if dx.unsigned_abs() > max || dy.unsigned_abs() > max {
+        return Err(format!(
+            "Delta ({}, {}) exceeds the maximum of {} pixels",
+            dx, dy, MAX_OVERLAY_DELTA
+        ));
+    }
+    Ok(())
+}
+
+// Applies a clamped delta to a position coordinate without overflowing
+fn offset_coordinate(current: i32, delta: i32) -> i32 {
+    current.saturating_add(delta.clamp(-MAX_OVERLAY_DELTA, MAX_OVERLAY_DELTA))
+}
+
+// Applies a clamped delta to a size dimension, keeping it within the overlay size bounds
+fn resize_dimension(current: u32, delta: i32) -> u32 {
+    let delta = delta.clamp(-MAX_OVERLAY_DELTA, MAX_OVERLAY_DELTA) as i64;
+    (current as i64 + delta).clamp(MIN_OVERLAY_SIZE as i64, MAX_OVERLAY_SIZE as i64) as u32
+}
+
+/// Moves the overlay by (dx, dy) physical pixels and returns the new position
+pub fn move_overlay_by(window: &WebviewWindow, dx: i32, dy: i32) -> Result<(i32, i32), String> {
+    let current_pos = window
+        .outer_position()
+        .map_err(|e| format!("Failed to get overlay position: {}", e))?;
+
+    let new_x = offset_coordinate(current_pos.x, dx);
+    let new_y = offset_coordinate(current_pos.y, dy);
+
+    window
+        .set_position(tauri::Position::Physical(tauri::PhysicalPosition {
+            x: new_x,
+            y: new_y,
+        }))
+        .map_err(|e| format!("Failed to move overlay: {}", e))?;
+
+    Ok((new_x, new_y))
+}
+
+/// Resizes the overlay by (dw, dh) physical pixels and returns the new size
+pub fn resize_overlay_by(window: &WebviewWindow, dw: i32, dh: i32) -> Result<(u32, u32), String> {
+    let current_size = window
+        .inner_size()
+        .map_err(|e| format!("Failed to get overlay size: {}", e))?;
+
+    let new_width = resize_dimension(current_size.width, dw);
+    let new_height = resize_dimension(current_size.height, dh);
+
+    window
+        .set_size(tauri::Size::Physical(tauri::PhysicalSize {
+            width: new_width,
+            height: new_height,
+        }))
+        .map_err(|e| format!("Failed to resize overlay: {}", e))?;
+
+    Ok((new_width, new_height))
+}
+
+/// Moves the overlay by a relative offset; deltas beyond MAX_OVERLAY_DELTA are rejected
+#[tauri::command]
+pub async fn move_overlay(dx: i32, dy: i32, app_handle: AppHandle) -> Result<(i32, i32), String> {
+    ensure_overlay_enabled(&app_handle)?;
+    validate_delta(dx, dy)?;
+
+    let window = app_handle
+        .get_webview_window(OVERLAY_LABEL)
+        .ok_or("Overlay window not found")?;
+    let new_position = move_overlay_by(&window, dx, dy)?;
+    ensure_overlay_click_through(&window);
+
+    log::info!("Overlay moved to {:?}", new_position);
+    Ok(new_position)
+}
+
+/// Resizes the overlay by a relative amount; deltas beyond MAX_OVERLAY_DELTA are rejected
+#[tauri::command]
+pub async fn resize_overlay(dw: i32, dh: i32, app_handle: AppHandle) -> Result<(u32, u32), String> {
+    ensure_overlay_enabled(&app_handle)?;
+    validate_delta(dw, dh)?;
+
+    let window = app_handle
+        .get_webview_window(OVERLAY_LABEL)
+        .ok_or("Overlay window not found")?;
+    let new_size = resize_overlay_by(&window, dw, dh)?;
+    ensure_overlay_click_through(&window);
+
+    log::info!("Overlay resized to {:?}", new_size);
+    Ok(new_size)
+}
+
+// Accepts #RGB, #RRGGBB and #RRGGBBAA
+pub fn is_valid_hex_color(color: &str) -> bool {
+    match color.strip_prefix('#') {
+        Some(hex) => matches!(hex.len(), 3 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        None => false,
+    }
+}
+
+/// Sets (or clears, when `color` is None) the overlay color used for an agent's messages
+#[tauri::command]
+pub async fn set_agent_color(
+    agent_id: String,
+    color: Option<String>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!(
+        "Setting overlay color for agent '{}' to {:?}",
+        agent_id,
+        color
+    );
+
+    if let Some(color) = &color {
+        if !is_valid_hex_color(color) {
+            return Err(format!("Invalid hex color: {}", color));
+        }
+    }
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| match color {
+        Some(color) => {
+            config.agent_colors.insert(agent_id, color);
+        }
+        None => {
+            config.agent_colors.remove(&agent_id);
+        }
+    })
+}
+
+/// Sets (or clears, when `icon` is None) the overlay icon used for an agent's messages
+#[tauri::command]
+pub async fn set_agent_icon(
+    agent_id: String,
+    icon: Option<String>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!(
+        "Setting overlay icon for agent '{}' to {:?}",
+        agent_id,
+        icon
+    );
+
+    shortcuts::update_config(&app_handle, &shortcut_state, |config| match icon {
+        Some(icon) => {
+            config.agent_icons.insert(agent_id, icon);
+        }
+        None => {
+            config.agent_icons.remove(&agent_id);
+        }
+    })
+}
+
+// Overlay flags captured when presentation mode is enabled, restored when it ends
+struct PresentationSnapshot {
+    always_on_top: bool,
+    visible: bool,
+}
+
+#[derive(Default)]
+pub struct PresentationModeState {
+    // Some while presentation mode is active
+    previous: Mutex<Option<PresentationSnapshot>>,
+}
+
+/// Keeps the overlay on top of everything (including fullscreen apps where the
+/// platform allows it) until disabled, then restores the previous flags exactly
+#[tauri::command]
+pub async fn set_overlay_presentation_mode(
+    enabled: bool,
+    presentation_state: State<'_, PresentationModeState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    ensure_overlay_enabled(&app_handle)?;
+    log::info!("Setting overlay presentation mode to {}", enabled);
+
+    let window = app_handle
+        .get_webview_window(OVERLAY_LABEL)
+        .ok_or("Overlay window not found")?;
+    let mut previous = presentation_state.previous.lock().unwrap();
+
+    if enabled {
+        if previous.is_none() {
+            *previous = Some(PresentationSnapshot {
+                always_on_top: window
+                    .is_always_on_top()
+                    .map_err(|e| format!("Failed to read overlay always-on-top: {}", e))?,
+                visible: window
+                    .is_visible()
+                    .map_err(|e| format!("Failed to read overlay visibility: {}", e))?,
+            });
+        }
+
+        // Toggling always-on-top re-raises the window above anything that took the top spot
+        window
+            .set_always_on_top(false)
+            .and_then(|_| window.set_always_on_top(true))
+            .map_err(|e| format!("Failed to raise overlay: {}", e))?;
+        window
+            .set_visible_on_all_workspaces(true)
+            .map_err(|e| format!("Failed to show overlay on all workspaces: {}", e))?;
+        window
+            .show()
+            .map_err(|e| format!("Failed to show overlay: {}", e))?;
+    } else {
+        let Some(snapshot) = previous.take() else {
+            return Ok(());
+        };
+
+        window
+            .set_visible_on_all_workspaces(false)
+            .map_err(|e| format!("Failed to restore overlay workspaces: {}", e))?;
+        window
+            .set_always_on_top(snapshot.always_on_top)
+            .map_err(|e| format!("Failed to restore overlay always-on-top: {}", e))?;
+        if !snapshot.visible {
+            window
+                .hide()
+                .map_err(|e| format!("Failed to hide overlay: {}", e))?;
+        }
+    }
+
+    ensure_overlay_click_through(&window);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_points_on_a_monitor() {
+        let (origin, size) = ((-1920, 0), (1920, 1080));
+        assert!(point_on_monitor(-1920, 0, origin, size));
+        assert!(point_on_monitor(-1, 1079, origin, size));
+        assert!(!point_on_monitor(0, 0, origin, size));
+        assert!(!point_on_monitor(-1920, 1080, origin, size));
+        assert!(!point_on_monitor(-1921, 500, origin, size));
+    }
+
+    #[test]
+    fn handles_monitors_at_the_i32_bounds() {
+        let origin = (i32::MAX - 100, 0);
+        assert!(point_on_monitor(i32::MAX, 0, origin, (u32::MAX, 100)));
+        assert!(!point_on_monitor(i32::MIN, 0, origin, (u32::MAX, 100)));
+    }
+
+    #[test]
+    fn rejects_extreme_deltas() {
+        for (dx, dy) in [(i32::MIN, 0), (0, i32::MIN), (i32::MAX, 0), (0, i32::MAX)] {
+            assert!(validate_delta(dx, dy).is_err(), "({}, {})", dx, dy);
+        }
+        assert!(validate_delta(MAX_OVERLAY_DELTA + 1, 0).is_err());
+        assert!(validate_delta(0, -MAX_OVERLAY_DELTA - 1).is_err());
+    }
+
+    #[test]
+    fn accepts_deltas_up_to_the_limit() {
+        assert!(validate_delta(0, 0).is_ok());
+        assert!(validate_delta(MAX_OVERLAY_DELTA, -MAX_OVERLAY_DELTA).is_ok());
+        assert!(validate_delta(-MAX_OVERLAY_DELTA, MAX_OVERLAY_DELTA).is_ok());
+    }
+
+    #[test]
+    fn clamps_extreme_move_deltas() {
+        assert_eq!(offset_coordinate(0, i32::MAX), MAX_OVERLAY_DELTA);
+        assert_eq!(offset_coordinate(0, i32::MIN), -MAX_OVERLAY_DELTA);
+        assert_eq!(offset_coordinate(100, -50), 50);
+    }
+
+    #[test]
+    fn saturates_positions_near_the_i32_bounds() {
+        assert_eq!(offset_coordinate(i32::MAX - 1, MAX_OVERLAY_DELTA), i32::MAX);
+        assert_eq!(offset_coordinate(i32::MAX, i32::MAX), i32::MAX);
+        assert_eq!(
+            offset_coordinate(i32::MIN + 1, -MAX_OVERLAY_DELTA),
+            i32::MIN
+        );
+        assert_eq!(offset_coordinate(i32::MIN, i32::MIN), i32::MIN);
+    }
 
-    StatusCode::OK
+    #[test]
+    fn clamps_resizes_below_the_minimum_size() {
+        assert_eq!(resize_dimension(MIN_OVERLAY_SIZE, -1), MIN_OVERLAY_SIZE);
+        assert_eq!(resize_dimension(500, i32::MIN), MIN_OVERLAY_SIZE);
+        assert_eq!(resize_dimension(0, 0), MIN_OVERLAY_SIZE);
+    }
+
+    #[test]
+    fn clamps_resizes_above_the_maximum_size() {
+        assert_eq!(resize_dimension(MAX_OVERLAY_SIZE, 1), MAX_OVERLAY_SIZE);
+        assert_eq!(resize_dimension(500, i32::MAX), MAX_OVERLAY_SIZE);
+        assert_eq!(resize_dimension(u32::MAX, 0), MAX_OVERLAY_SIZE);
+    }
+
+    #[test]
+    fn applies_resizes_within_bounds() {
+        assert_eq!(resize_dimension(500, 100), 600);
+        assert_eq!(resize_dimension(500, -100), 400);
+    }
 }
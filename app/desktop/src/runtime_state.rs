@@ -0,0 +1,239 @@
+// In src-tauri/src/runtime_state.rs
+
+use crate::commands::{self, AgentInfo, CommandRecord, HeldCommand};
+use crate::config_diff::REDACTED;
+use crate::shortcuts::{self, AppConfig, UnifiedShortcutState};
+use crate::{AppSettings, CommandState, OverlayMessage, OverlayState};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Emitter, State};
+
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Everything the backend holds in memory, for QA fixtures and machine migration
+#[derive(Serialize, Deserialize)]
+pub struct RuntimeSnapshot {
+    version: u32,
+    config: AppConfig,
+    overlay_messages: Vec<OverlayMessage>,
+    // Oldest first
+    command_history: Vec<CommandRecord>,
+    pending_commands: HashMap<String, String>,
+    // Oldest first
+    held_commands: Vec<HeldCommand>,
+    // Derived from the config; informational only and ignored on import
+    #[serde(default, skip_deserializing)]
+    agents: Vec<AgentInfo>,
+}
+
+fn redact_secrets(config: &mut AppConfig) {
+    if config.ollama_api_key.is_some() {
+        config.ollama_api_key = Some(REDACTED.to_string());
+    }
+    for profile in config.ollama_profiles.values_mut() {
+        if profile.api_key.is_some() {
+            profile.api_key = Some(REDACTED.to_string());
+        }
+    }
+    for backend in config.ollama_failover_urls.iter_mut() {
+        if backend.api_key.is_some() {
+            backend.api_key = Some(REDACTED.to_string());
+        }
+    }
+    // Header values are often credentials (Authorization, cookies, tokens)
+    for value in config.proxy_extra_headers.values_mut() {
+        *value = REDACTED.to_string();
+    }
+}
+
+// Redacted secrets in an imported config keep the values currently configured
+fn restore_secrets(config: &mut AppConfig, current: &AppConfig) {
+    if config.ollama_api_key.as_deref() == Some(REDACTED) {
+        config.ollama_api_key = current.ollama_api_key.clone();
+    }
+    for (name, profile) in config.ollama_profiles.iter_mut() {
+        if profile.api_key.as_deref() == Some(REDACTED) {
+            profile.api_key = current
+                .ollama_profiles
+                .get(name)
+                .and_then(|p| p.api_key.clone());
+        }
+    }
+    for backend in config.ollama_failover_urls.iter_mut() {
+        if backend.api_key.as_deref() == Some(REDACTED) {
+            backend.api_key = current
+                .ollama_failover_urls
+                .iter()
+                .find(|b| b.url == backend.url)
+                .and_then(|b| b.api_key.clone());
+        }
+    }
+    // A redacted header with no current value is dropped rather than sent as "<redacted>"
+    config.proxy_extra_headers.retain(|name, value| {
+        if value != REDACTED {
+            return true;
+        }
+        match current.proxy_extra_headers.get(name) {
+            Some(current_value) => {
+                *value = current_value.clone();
+                true
+            }
+            None => false,
+        }
+    });
+}
+
+// Checks every section, collecting all problems rather than stopping at the first
+fn validate_snapshot(snapshot: &RuntimeSnapshot) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    if snapshot.version != SNAPSHOT_VERSION {
+        errors.push(format!(
+            "Unsupported snapshot version {} (expected {})",
+            snapshot.version, SNAPSHOT_VERSION
+        ));
+    }
+
+    for error in crate::validation::validate(&snapshot.config).error_messages() {
+        errors.push(format!("config.{}", error));
+    }
+
+    let mut ids = HashSet::new();
+    for message in &snapshot.overlay_messages {
+        let id = message.id.as_str();
+        if id.is_empty() {
+            errors.push("overlay_messages: message with an empty id".to_string());
+        } else if !ids.insert(id) {
+            errors.push(format!("overlay_messages: duplicate id {}", id));
+        }
+    }
+
+    let messages = snapshot
+        .command_history
+        .iter()
+        .map(|record| ("command_history", &record.command))
+        .chain(
+            snapshot
+                .held_commands
+                .iter()
+                .map(|held| ("held_commands", &held.command)),
+        );
+    for (section, command) in messages {
+        if let Err(e) = commands::resolve_message_type(Some(command.message_type.clone())) {
+            errors.push(format!("{}: {}", section, e));
+        }
+    }
+
+    if snapshot.command_history.len() > commands::MAX_COMMAND_HISTORY {
+        errors.push(format!(
+            "command_history: {} entries exceeds the maximum of {}",
+            snapshot.command_history.len(),
+            commands::MAX_COMMAND_HISTORY
+        ));
+    }
+    if snapshot.held_commands.len() > commands::MAX_HELD_COMMANDS {
+        errors.push(format!(
+            "held_commands: {} entries exceeds the maximum of {}",
+            snapshot.held_commands.len(),
+            commands::MAX_HELD_COMMANDS
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Captures config, overlay messages, command history and the agent registry.
+/// API keys are redacted unless `include_secrets` is set
+#[tauri::command]
+pub async fn export_runtime_state(
+    include_secrets: Option<bool>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    overlay_state: State<'_, OverlayState>,
+    command_state: State<'_, CommandState>,
+) -> Result<RuntimeSnapshot, String> {
+    let mut config = shortcut_state.config.lock().unwrap().clone();
+    let agents = commands::known_agents(&config);
+    if !include_secrets.unwrap_or(false) {
+        redact_secrets(&mut config);
+    }
+
+    log::info!("Exporting runtime state");
+
+    Ok(RuntimeSnapshot {
+        version: SNAPSHOT_VERSION,
+        config,
+        overlay_messages: overlay_state.messages.lock().unwrap().clone(),
+        command_history: command_state
+            .history
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect(),
+        pending_commands: command_state.pending_commands.lock().unwrap().clone(),
+        held_commands: command_state
+            .held_commands
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect(),
+        agents,
+    })
+}
+
+/// Restores a snapshot after validating every section, then persists the config
+/// and re-registers the shortcuts; nothing is changed when validation fails
+#[tauri::command]
+pub async fn import_runtime_state(
+    snapshot: RuntimeSnapshot,
+    settings: State<'_, AppSettings>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    overlay_state: State<'_, OverlayState>,
+    command_state: State<'_, CommandState>,
+    app_handle: AppHandle,
+) -> Result<(), Vec<String>> {
+    validate_snapshot(&snapshot)?;
+
+    log::info!(
+        "Importing runtime state: {} overlay message(s), {} command record(s)",
+        snapshot.overlay_messages.len(),
+        snapshot.command_history.len()
+    );
+
+    let mut config = snapshot.config;
+    restore_secrets(&mut config, &shortcut_state.config.lock().unwrap());
+
+    let (ollama_url, ollama_api_key) = (config.ollama_url.clone(), config.ollama_api_key.clone());
+    shortcuts::update_config(&app_handle, &shortcut_state, |current| *current = config)
+        .map_err(|e| vec![e])?;
+    *settings.ollama_url.lock().unwrap() = ollama_url;
+    *settings.ollama_api_key.lock().unwrap() = ollama_api_key;
+    shortcuts::refresh_shortcuts(&app_handle).map_err(|e| vec![e])?;
+
+    *overlay_state.messages.lock().unwrap() = snapshot.overlay_messages.clone();
+    if let Err(e) = app_handle.emit("overlay-messages-updated", &snapshot.overlay_messages) {
+        log::warn!("Failed to emit overlay-messages-updated event: {}", e);
+    }
+
+    // Keep new event ids above the imported ones so Last-Event-ID replay stays ordered
+    let imported_event_id = snapshot
+        .command_history
+        .iter()
+        .filter_map(|record| record.event_id)
+        .max()
+        .unwrap_or(0);
+    {
+        let mut last_event_id = command_state.last_event_id.lock().unwrap();
+        *last_event_id = (*last_event_id).max(imported_event_id);
+    }
+    *command_state.history.lock().unwrap() = snapshot.command_history.into();
+    *command_state.pending_commands.lock().unwrap() = snapshot.pending_commands;
+    *command_state.held_commands.lock().unwrap() = snapshot.held_commands.into();
+
+    Ok(())
+}
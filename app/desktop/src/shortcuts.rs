@@ -1,21 +1,314 @@
-use crate::CommandState;
+use crate::overlay;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Mutex;
 use tauri::{AppHandle, Emitter, Manager, State};
 
 // Comprehensive app configuration
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct AppConfig {
+    // Set by an admin-provisioned settings.json (or OBSERVER_CONFIG_LOCKED=1);
+    // commands can neither change it nor persist anything while it is set
+    #[serde(default)]
+    pub config_locked: bool,
+
     pub shortcuts: UnifiedShortcutConfig,
     pub ollama_url: Option<String>,
+    #[serde(default)]
+    pub ollama_api_key: Option<String>,
+
+    // Named backend presets: profile name -> url/key
+    #[serde(default)]
+    pub ollama_profiles: HashMap<String, OllamaProfile>,
+
+    // User-Agent sent on proxied and check requests; None means "Observer/<version>"
+    #[serde(default)]
+    pub user_agent: Option<String>,
+
+    // Seconds idle upstream connections stay pooled; None keeps the HTTP
+    // client's default and 0 closes them as soon as they go idle
+    #[serde(default)]
+    pub proxy_idle_timeout_secs: Option<u64>,
+
+    // Headers added to every proxied request unless the caller already set them,
+    // e.g. an organization id the backend requires
+    #[serde(default)]
+    pub proxy_extra_headers: HashMap<String, String>,
+
+    // Backends tried in order when the active one becomes unreachable
+    #[serde(default)]
+    pub ollama_failover_urls: Vec<BackendEntry>,
+
+    // Reactions when the status monitor finds the backend unreachable (once per outage)
+    #[serde(default)]
+    pub backend_down_overlay: bool,
+    #[serde(default)]
+    pub backend_down_notification: bool,
+    #[serde(default)]
+    pub backend_down_failover: bool,
+
+    // Display names per agent: agent_id -> name
+    #[serde(default)]
+    pub agent_names: HashMap<String, String>,
+    // Overlay styling per agent: agent_id -> hex color / icon
+    #[serde(default)]
+    pub agent_colors: HashMap<String, String>,
+    #[serde(default)]
+    pub agent_icons: HashMap<String, String>,
+    // Overlay window behavior
+    #[serde(default = "default_true")]
+    pub overlay_skip_taskbar: bool,
+    // Native resize handles; off by default so the HUD only resizes via hotkeys
+    #[serde(default)]
+    pub overlay_resizable: bool,
+    // Whether the overlay can take keyboard focus and forward key events to its
+    // frontend; independent of click-through, which always ignores the mouse
+    #[serde(default = "default_true")]
+    pub overlay_accept_keyboard: bool,
+    // Screen corner/edge the overlay is pinned to (e.g. "top-right");
+    // None keeps absolute positioning
+    #[serde(default)]
+    pub overlay_anchor: Option<String>,
+    // Also re-register the global shortcuts when the display setup changes
+    // (e.g. docking a laptop); the overlay is re-validated either way
+    #[serde(default)]
+    pub reregister_shortcuts_on_display_change: bool,
+    // Named overlay geometry presets: layout name -> layout
+    #[serde(default)]
+    pub overlay_layouts: HashMap<String, OverlayLayout>,
+    // Where the overlay was last moved to; restored at startup when it is still
+    // on a connected monitor
+    #[serde(default)]
+    pub overlay_position: Option<OverlayPosition>,
+    // Toggling only shows the overlay when it has messages, and it hides
+    // itself once the last message is cleared or expires
+    #[serde(default)]
+    pub overlay_hide_when_empty: bool,
+    // When false no overlay window is created and the overlay shortcuts are
+    // not registered (their bindings are kept, so re-enabling restores them)
+    #[serde(default = "default_true")]
+    pub overlay_enabled: bool,
+
+    // When set, every broadcast command is appended to this NDJSON file
+    // (relative paths live in the app data directory)
+    #[serde(default)]
+    pub command_audit_file: Option<String>,
+    // Replace command payloads with "<redacted>" in the audit file
+    #[serde(default)]
+    pub command_audit_redact_payloads: bool,
+
+    // When set, /commands-stream connections are closed after this long with a
+    // final {"type":"reconnect"} event so stale clients can't pile up
+    #[serde(default)]
+    pub sse_max_lifetime_secs: Option<u64>,
+
+    // Opt-in switch for all synthesized input (/click, /key, /type, /scroll and
+    // the matching commands); off until the user enables it
+    #[serde(default)]
+    pub controls_enabled: bool,
+    // Minimum spacing between synthesized input events (clicks, key presses,
+    // typed characters) for target apps that drop fast input; None means 0
+    #[serde(default)]
+    pub input_event_delay_ms: Option<u64>,
+    // How long a closing window waits for the frontend's ack_window_close
+    // before being hidden to the tray; None means hide immediately
+    #[serde(default)]
+    pub close_grace_period_ms: Option<u64>,
+    // When set, only these agents may use the input control endpoints. The
+    // agent id is declared by the caller, so this filters the app's own agents
+    // rather than authenticating anyone
+    #[serde(default)]
+    pub click_allowed_agents: Option<Vec<String>>,
+
+    // When set, identical notifications within this window are suppressed
+    #[serde(default)]
+    pub notification_dedupe_window_ms: Option<u64>,
+
+    // Do-not-disturb window for notifications (and optionally the overlay)
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+
+    // Keep the launcher window hidden at startup (tray / hotkeys still work)
+    #[serde(default)]
+    pub start_hidden: bool,
+
+    // UI theme preference shared by the main window and the overlay:
+    // "light", "dark" or "system" (None means the frontend default)
+    #[serde(default)]
+    pub theme: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct OllamaProfile {
+    pub url: Option<String>,
+    pub api_key: Option<String>,
+}
+
+// A backend URL with the API key to send it; without a key the global
+// ollama_api_key is used
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(from = "BackendEntryRepr")]
+pub struct BackendEntry {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+}
+
+// Older configs list failover backends as bare URL strings
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BackendEntryRepr {
+    Url(String),
+    Entry {
+        url: String,
+        #[serde(default)]
+        api_key: Option<String>,
+    },
+}
+
+impl From<BackendEntryRepr> for BackendEntry {
+    fn from(repr: BackendEntryRepr) -> Self {
+        match repr {
+            BackendEntryRepr::Url(url) => BackendEntry { url, api_key: None },
+            BackendEntryRepr::Entry { url, api_key } => BackendEntry { url, api_key },
+        }
+    }
+}
+
+// An agent's shortcut key, optionally limited to when the focused window's title
+// contains `active_when_app` (case-insensitive). Saved as the bare key when there
+// is no condition, so plain agent_id -> key maps keep loading
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(from = "AgentShortcutRepr", into = "AgentShortcutRepr")]
+pub struct AgentShortcut {
+    pub key: String,
+    pub active_when_app: Option<String>,
+}
+
+impl AgentShortcut {
+    pub fn new(key: String) -> Self {
+        Self {
+            key,
+            active_when_app: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum AgentShortcutRepr {
+    Key(String),
+    Entry {
+        key: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        active_when_app: Option<String>,
+    },
+}
+
+impl From<AgentShortcutRepr> for AgentShortcut {
+    fn from(repr: AgentShortcutRepr) -> Self {
+        match repr {
+            AgentShortcutRepr::Key(key) => AgentShortcut::new(key),
+            AgentShortcutRepr::Entry {
+                key,
+                active_when_app,
+            } => AgentShortcut {
+                key,
+                active_when_app,
+            },
+        }
+    }
+}
+
+impl From<AgentShortcut> for AgentShortcutRepr {
+    fn from(shortcut: AgentShortcut) -> Self {
+        match shortcut.active_when_app {
+            None => AgentShortcutRepr::Key(shortcut.key),
+            Some(app) => AgentShortcutRepr::Entry {
+                key: shortcut.key,
+                active_when_app: Some(app),
+            },
+        }
+    }
+}
+
+// Saved overlay geometry in physical pixels; with an anchor set the position is
+// recomputed from it on apply
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct OverlayLayout {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub anchor: Option<String>,
+    // Applied by the frontend (0.0-1.0); None keeps the current opacity
+    #[serde(default)]
+    pub opacity: Option<f64>,
+}
+
+// Top-left corner of the overlay in physical pixels
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub struct OverlayPosition {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct QuietHours {
+    // Local times as "HH:MM"; a start after the end spans midnight
+    pub start: String,
+    pub end: String,
+    // Lowercase three-letter weekdays ("mon".."sun") the window starts on; empty means every day
+    #[serde(default)]
+    pub days: Vec<String>,
+    // Also hide the overlay while quiet (messages are still recorded)
+    #[serde(default)]
+    pub hide_overlay: bool,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            config_locked: false,
             shortcuts: UnifiedShortcutConfig::default(),
             ollama_url: Some("http://localhost:11434".to_string()),
+            ollama_api_key: None,
+            ollama_profiles: HashMap::new(),
+            user_agent: None,
+            proxy_idle_timeout_secs: None,
+            proxy_extra_headers: HashMap::new(),
+            ollama_failover_urls: Vec::new(),
+            backend_down_overlay: false,
+            backend_down_notification: false,
+            backend_down_failover: false,
+            agent_names: HashMap::new(),
+            agent_colors: HashMap::new(),
+            agent_icons: HashMap::new(),
+            overlay_skip_taskbar: true,
+            overlay_resizable: false,
+            overlay_accept_keyboard: true,
+            overlay_anchor: None,
+            reregister_shortcuts_on_display_change: false,
+            overlay_layouts: HashMap::new(),
+            overlay_position: None,
+            overlay_hide_when_empty: false,
+            overlay_enabled: true,
+            command_audit_file: None,
+            command_audit_redact_payloads: false,
+            sse_max_lifetime_secs: None,
+            controls_enabled: false,
+            input_event_delay_ms: None,
+            close_grace_period_ms: None,
+            click_allowed_agents: None,
+            notification_dedupe_window_ms: None,
+            quiet_hours: None,
+            start_hidden: false,
+            theme: None,
         }
     }
 }
@@ -28,13 +321,168 @@ pub struct UnifiedShortcutConfig {
     pub overlay_move_down: Option<String>,
     pub overlay_move_left: Option<String>,
     pub overlay_move_right: Option<String>,
+    // Diagonal moves, applying both offsets in one keystroke
+    #[serde(default)]
+    pub overlay_move_up_left: Option<String>,
+    #[serde(default)]
+    pub overlay_move_up_right: Option<String>,
+    #[serde(default)]
+    pub overlay_move_down_left: Option<String>,
+    #[serde(default)]
+    pub overlay_move_down_right: Option<String>,
     pub overlay_resize_up: Option<String>,
     pub overlay_resize_down: Option<String>,
     pub overlay_resize_left: Option<String>,
     pub overlay_resize_right: Option<String>,
+    #[serde(default)]
+    pub overlay_clear: Option<String>,
+    // Moves the overlay back to its initial position and size
+    #[serde(default)]
+    pub overlay_reset: Option<String>,
+    // Centers the overlay on its current monitor
+    #[serde(default)]
+    pub overlay_center: Option<String>,
+    // Moves the overlay flush against an edge of its current monitor
+    #[serde(default)]
+    pub overlay_snap_top: Option<String>,
+    #[serde(default)]
+    pub overlay_snap_bottom: Option<String>,
+    #[serde(default)]
+    pub overlay_snap_left: Option<String>,
+    #[serde(default)]
+    pub overlay_snap_right: Option<String>,
 
-    // Agent shortcuts: agent_id -> shortcut_key
-    pub agent_shortcuts: HashMap<String, String>,
+    // Agent shortcuts: agent_id -> shortcut_key (with an optional app condition)
+    pub agent_shortcuts: HashMap<String, AgentShortcut>,
+
+    // Broadcast shortcuts: action -> shortcut_key, sent to every agent
+    #[serde(default)]
+    pub agent_broadcast_shortcuts: HashMap<String, String>,
+}
+
+// Action names for agent / broadcast bindings, e.g. "agent:<id>" or "broadcast:<action>";
+// overlay bindings use their config field name
+pub const AGENT_ACTION_PREFIX: &str = "agent:";
+pub const BROADCAST_ACTION_PREFIX: &str = "broadcast:";
+
+impl UnifiedShortcutConfig {
+    /// The overlay shortcut fields, keyed by their config field name
+    pub fn overlay_bindings(&self) -> Vec<(&'static str, &Option<String>)> {
+        vec![
+            ("overlay_toggle", &self.overlay_toggle),
+            ("overlay_move_up", &self.overlay_move_up),
+            ("overlay_move_down", &self.overlay_move_down),
+            ("overlay_move_left", &self.overlay_move_left),
+            ("overlay_move_right", &self.overlay_move_right),
+            ("overlay_move_up_left", &self.overlay_move_up_left),
+            ("overlay_move_up_right", &self.overlay_move_up_right),
+            ("overlay_move_down_left", &self.overlay_move_down_left),
+            ("overlay_move_down_right", &self.overlay_move_down_right),
+            ("overlay_resize_up", &self.overlay_resize_up),
+            ("overlay_resize_down", &self.overlay_resize_down),
+            ("overlay_resize_left", &self.overlay_resize_left),
+            ("overlay_resize_right", &self.overlay_resize_right),
+            ("overlay_clear", &self.overlay_clear),
+            ("overlay_reset", &self.overlay_reset),
+            ("overlay_center", &self.overlay_center),
+            ("overlay_snap_top", &self.overlay_snap_top),
+            ("overlay_snap_bottom", &self.overlay_snap_bottom),
+            ("overlay_snap_left", &self.overlay_snap_left),
+            ("overlay_snap_right", &self.overlay_snap_right),
+        ]
+    }
+
+    pub fn overlay_bindings_mut(&mut self) -> Vec<(&'static str, &mut Option<String>)> {
+        vec![
+            ("overlay_toggle", &mut self.overlay_toggle),
+            ("overlay_move_up", &mut self.overlay_move_up),
+            ("overlay_move_down", &mut self.overlay_move_down),
+            ("overlay_move_left", &mut self.overlay_move_left),
+            ("overlay_move_right", &mut self.overlay_move_right),
+            ("overlay_move_up_left", &mut self.overlay_move_up_left),
+            ("overlay_move_up_right", &mut self.overlay_move_up_right),
+            ("overlay_move_down_left", &mut self.overlay_move_down_left),
+            ("overlay_move_down_right", &mut self.overlay_move_down_right),
+            ("overlay_resize_up", &mut self.overlay_resize_up),
+            ("overlay_resize_down", &mut self.overlay_resize_down),
+            ("overlay_resize_left", &mut self.overlay_resize_left),
+            ("overlay_resize_right", &mut self.overlay_resize_right),
+            ("overlay_clear", &mut self.overlay_clear),
+            ("overlay_reset", &mut self.overlay_reset),
+            ("overlay_center", &mut self.overlay_center),
+            ("overlay_snap_top", &mut self.overlay_snap_top),
+            ("overlay_snap_bottom", &mut self.overlay_snap_bottom),
+            ("overlay_snap_left", &mut self.overlay_snap_left),
+            ("overlay_snap_right", &mut self.overlay_snap_right),
+        ]
+    }
+
+    /// Every non-empty binding as (action name, key)
+    pub fn all_bindings(&self) -> Vec<(String, String)> {
+        let mut bindings: Vec<(String, String)> = self
+            .overlay_bindings()
+            .into_iter()
+            .filter_map(|(name, key)| key.clone().map(|key| (name.to_string(), key)))
+            .collect();
+        bindings.extend(self.agent_shortcuts.iter().map(|(agent_id, shortcut)| {
+            (
+                format!("{}{}", AGENT_ACTION_PREFIX, agent_id),
+                shortcut.key.clone(),
+            )
+        }));
+        bindings.extend(self.agent_broadcast_shortcuts.iter().map(|(action, key)| {
+            (
+                format!("{}{}", BROADCAST_ACTION_PREFIX, action),
+                key.clone(),
+            )
+        }));
+        bindings.retain(|(_, key)| !key.is_empty());
+        bindings
+    }
+
+    /// Sets (or clears, when `key` is None) the key bound to an action name,
+    /// returning the previous key
+    pub fn set_binding(
+        &mut self,
+        action: &str,
+        key: Option<String>,
+    ) -> Result<Option<String>, String> {
+        let key = key.filter(|key| !key.is_empty());
+
+        if let Some(agent_id) = action.strip_prefix(AGENT_ACTION_PREFIX) {
+            // Rebinding keeps the agent's app condition
+            return Ok(match (key, self.agent_shortcuts.get_mut(agent_id)) {
+                (Some(key), Some(shortcut)) => Some(std::mem::replace(&mut shortcut.key, key)),
+                (Some(key), None) => {
+                    self.agent_shortcuts
+                        .insert(agent_id.to_string(), AgentShortcut::new(key));
+                    None
+                }
+                (None, _) => self
+                    .agent_shortcuts
+                    .remove(agent_id)
+                    .map(|shortcut| shortcut.key),
+            });
+        }
+
+        if let Some(broadcast_action) = action.strip_prefix(BROADCAST_ACTION_PREFIX) {
+            return Ok(match key {
+                Some(key) => self
+                    .agent_broadcast_shortcuts
+                    .insert(broadcast_action.to_string(), key),
+                None => self.agent_broadcast_shortcuts.remove(broadcast_action),
+            });
+        }
+
+        match self
+            .overlay_bindings_mut()
+            .into_iter()
+            .find(|(name, _)| *name == action)
+        {
+            Some((_, binding)) => Ok(std::mem::replace(binding, key)),
+            None => Err(format!("Unknown shortcut action: {}", action)),
+        }
+    }
 }
 
 impl Default for UnifiedShortcutConfig {
@@ -48,11 +496,23 @@ impl Default for UnifiedShortcutConfig {
                 overlay_move_down: Some("Alt+ArrowDown".to_string()),
                 overlay_move_left: Some("Alt+ArrowLeft".to_string()),
                 overlay_move_right: Some("Alt+ArrowRight".to_string()),
+                overlay_move_up_left: None,
+                overlay_move_up_right: None,
+                overlay_move_down_left: None,
+                overlay_move_down_right: None,
                 overlay_resize_up: Some("Alt+Shift+ArrowUp".to_string()),
                 overlay_resize_down: Some("Alt+Shift+ArrowDown".to_string()),
                 overlay_resize_left: Some("Alt+Shift+ArrowLeft".to_string()),
                 overlay_resize_right: Some("Alt+Shift+ArrowRight".to_string()),
+                overlay_clear: Some("Alt+Shift+B".to_string()),
+                overlay_reset: None,
+                overlay_center: None,
+                overlay_snap_top: None,
+                overlay_snap_bottom: None,
+                overlay_snap_left: None,
+                overlay_snap_right: None,
                 agent_shortcuts: HashMap::new(),
+                agent_broadcast_shortcuts: HashMap::new(),
             }
         }
         #[cfg(not(target_os = "windows"))]
@@ -63,11 +523,23 @@ impl Default for UnifiedShortcutConfig {
                 overlay_move_down: Some("Cmd+ArrowDown".to_string()),
                 overlay_move_left: Some("Cmd+ArrowLeft".to_string()),
                 overlay_move_right: Some("Cmd+ArrowRight".to_string()),
+                overlay_move_up_left: None,
+                overlay_move_up_right: None,
+                overlay_move_down_left: None,
+                overlay_move_down_right: None,
                 overlay_resize_up: Some("Cmd+Shift+ArrowUp".to_string()),
                 overlay_resize_down: Some("Cmd+Shift+ArrowDown".to_string()),
                 overlay_resize_left: Some("Cmd+Shift+ArrowLeft".to_string()),
                 overlay_resize_right: Some("Cmd+Shift+ArrowRight".to_string()),
+                overlay_clear: Some("Cmd+Shift+B".to_string()),
+                overlay_reset: None,
+                overlay_center: None,
+                overlay_snap_top: None,
+                overlay_snap_bottom: None,
+                overlay_snap_left: None,
+                overlay_snap_right: None,
                 agent_shortcuts: HashMap::new(),
+                agent_broadcast_shortcuts: HashMap::new(),
             }
         }
     }
@@ -76,6 +548,56 @@ impl Default for UnifiedShortcutConfig {
 pub struct UnifiedShortcutState {
     pub config: Mutex<AppConfig>,
     pub registered_shortcuts: Mutex<Vec<String>>,
+    // Currently registered bindings, looked up by the global shortcut handler
+    bindings: Mutex<Vec<ShortcutBinding>>,
+    // Outcome of the last registration pass for every configured binding
+    status: Mutex<Vec<ShortcutStatus>>,
+    // Bindings skipped in the last registration pass because an earlier binding
+    // already claimed the same shortcut
+    conflicts: Mutex<Vec<String>>,
+    // Most recent handled shortcut presses, oldest first
+    activity: Mutex<VecDeque<ShortcutActivation>>,
+}
+
+impl UnifiedShortcutState {
+    pub fn new(config: AppConfig) -> Self {
+        Self {
+            config: Mutex::new(config),
+            registered_shortcuts: Mutex::new(Vec::new()),
+            bindings: Mutex::new(Vec::new()),
+            status: Mutex::new(Vec::new()),
+            conflicts: Mutex::new(Vec::new()),
+            activity: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn record_activation(&self, binding: &ShortcutBinding) {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+
+        let mut activity = self.activity.lock().unwrap();
+        if activity.len() >= MAX_SHORTCUT_ACTIVITY {
+            activity.pop_front();
+        }
+        activity.push_back(ShortcutActivation {
+            key: binding.key.clone(),
+            action: binding.action.name(),
+            timestamp,
+        });
+    }
+}
+
+// Number of shortcut presses kept for get_shortcut_activity
+const MAX_SHORTCUT_ACTIVITY: usize = 100;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ShortcutActivation {
+    key: String,
+    action: String,
+    // Milliseconds since the Unix epoch
+    timestamp: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -85,11 +607,189 @@ enum ShortcutAction {
     OverlayMoveDown,
     OverlayMoveLeft,
     OverlayMoveRight,
+    OverlayMoveUpLeft,
+    OverlayMoveUpRight,
+    OverlayMoveDownLeft,
+    OverlayMoveDownRight,
     OverlayResizeUp,
     OverlayResizeDown,
     OverlayResizeLeft,
     OverlayResizeRight,
-    AgentToggle(String), // agent_id
+    OverlayClear,
+    OverlayReset,
+    OverlayCenter,
+    OverlaySnap(overlay::SnapEdge),
+    AgentToggle(String),    // agent_id
+    AgentBroadcast(String), // action
+}
+
+impl ShortcutAction {
+    // Action name as used by UnifiedShortcutConfig::all_bindings
+    fn name(&self) -> String {
+        match self {
+            ShortcutAction::OverlayToggle => "overlay_toggle".to_string(),
+            ShortcutAction::OverlayMoveUp => "overlay_move_up".to_string(),
+            ShortcutAction::OverlayMoveDown => "overlay_move_down".to_string(),
+            ShortcutAction::OverlayMoveLeft => "overlay_move_left".to_string(),
+            ShortcutAction::OverlayMoveRight => "overlay_move_right".to_string(),
+            ShortcutAction::OverlayMoveUpLeft => "overlay_move_up_left".to_string(),
+            ShortcutAction::OverlayMoveUpRight => "overlay_move_up_right".to_string(),
+            ShortcutAction::OverlayMoveDownLeft => "overlay_move_down_left".to_string(),
+            ShortcutAction::OverlayMoveDownRight => "overlay_move_down_right".to_string(),
+            ShortcutAction::OverlayResizeUp => "overlay_resize_up".to_string(),
+            ShortcutAction::OverlayResizeDown => "overlay_resize_down".to_string(),
+            ShortcutAction::OverlayResizeLeft => "overlay_resize_left".to_string(),
+            ShortcutAction::OverlayResizeRight => "overlay_resize_right".to_string(),
+            ShortcutAction::OverlayClear => "overlay_clear".to_string(),
+            ShortcutAction::OverlayReset => "overlay_reset".to_string(),
+            ShortcutAction::OverlayCenter => "overlay_center".to_string(),
+            ShortcutAction::OverlaySnap(edge) => format!("overlay_snap_{}", edge.name()),
+            ShortcutAction::AgentToggle(agent_id) => format!("{}{}", AGENT_ACTION_PREFIX, agent_id),
+            ShortcutAction::AgentBroadcast(action) => {
+                format!("{}{}", BROADCAST_ACTION_PREFIX, action)
+            }
+        }
+    }
+}
+
+// Every overlay action, in the order they are listed to callers
+const OVERLAY_ACTIONS: [ShortcutAction; 20] = [
+    ShortcutAction::OverlayToggle,
+    ShortcutAction::OverlayMoveUp,
+    ShortcutAction::OverlayMoveDown,
+    ShortcutAction::OverlayMoveLeft,
+    ShortcutAction::OverlayMoveRight,
+    ShortcutAction::OverlayMoveUpLeft,
+    ShortcutAction::OverlayMoveUpRight,
+    ShortcutAction::OverlayMoveDownLeft,
+    ShortcutAction::OverlayMoveDownRight,
+    ShortcutAction::OverlayResizeUp,
+    ShortcutAction::OverlayResizeDown,
+    ShortcutAction::OverlayResizeLeft,
+    ShortcutAction::OverlayResizeRight,
+    ShortcutAction::OverlayClear,
+    ShortcutAction::OverlayReset,
+    ShortcutAction::OverlayCenter,
+    ShortcutAction::OverlaySnap(overlay::SnapEdge::Top),
+    ShortcutAction::OverlaySnap(overlay::SnapEdge::Bottom),
+    ShortcutAction::OverlaySnap(overlay::SnapEdge::Left),
+    ShortcutAction::OverlaySnap(overlay::SnapEdge::Right),
+];
+
+#[derive(Serialize, Debug, Clone, Copy)]
+pub enum ShortcutRegistrationState {
+    Registered,
+    FailedToRegister,
+    Conflict,
+    Disabled,
+    Unparseable,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ShortcutStatus {
+    action: String,
+    key: String,
+    state: ShortcutRegistrationState,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum ShortcutPreviewOutcome {
+    WouldRegister,
+    Conflict,
+    Disabled,
+    Unparseable,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ShortcutPreview {
+    action: String,
+    key: String,
+    outcome: ShortcutPreviewOutcome,
+    message: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ShortcutPreviewReport {
+    // False when saving would leave a binding conflicting or unparseable
+    clean: bool,
+    bindings: Vec<ShortcutPreview>,
+}
+
+/// Predicts the registration outcome of every binding in `config` without
+/// registering anything; failures from other apps holding a key only show up
+/// on a real registration
+fn preview_bindings(
+    config: &UnifiedShortcutConfig,
+    overlay_enabled: bool,
+) -> ShortcutPreviewReport {
+    let overlay_names: Vec<&str> = config
+        .overlay_bindings()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+
+    let bindings = config.all_bindings();
+    let parsed: Vec<Option<tauri_plugin_global_shortcut::Shortcut>> = bindings
+        .iter()
+        .map(|(action, key)| {
+            if !overlay_enabled && overlay_names.contains(&action.as_str()) {
+                None
+            } else {
+                parse_shortcut_string(key)
+            }
+        })
+        .collect();
+
+    let previews: Vec<ShortcutPreview> = bindings
+        .iter()
+        .enumerate()
+        .map(|(i, (action, key))| {
+            let (outcome, message) = if !overlay_enabled && overlay_names.contains(&action.as_str())
+            {
+                (
+                    ShortcutPreviewOutcome::Disabled,
+                    Some("Overlay disabled".to_string()),
+                )
+            } else if let Some(shortcut) = parsed[i] {
+                let others: Vec<&str> = bindings
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i && parsed[*j] == Some(shortcut))
+                    .map(|(_, (other, _))| other.as_str())
+                    .collect();
+                if others.is_empty() {
+                    (ShortcutPreviewOutcome::WouldRegister, None)
+                } else {
+                    (
+                        ShortcutPreviewOutcome::Conflict,
+                        Some(format!("Also bound to {}", others.join(", "))),
+                    )
+                }
+            } else {
+                let error = normalize_shortcut(key)
+                    .err()
+                    .unwrap_or_else(|| format!("Unknown shortcut: {}", key));
+                (ShortcutPreviewOutcome::Unparseable, Some(error))
+            };
+            ShortcutPreview {
+                action: action.clone(),
+                key: key.clone(),
+                outcome,
+                message,
+            }
+        })
+        .collect();
+
+    ShortcutPreviewReport {
+        clean: previews.iter().all(|preview| {
+            matches!(
+                preview.outcome,
+                ShortcutPreviewOutcome::WouldRegister | ShortcutPreviewOutcome::Disabled
+            )
+        }),
+        bindings: previews,
+    }
 }
 
 // Tauri commands
@@ -109,6 +809,106 @@ pub async fn get_registered_shortcuts(
     Ok(shortcuts)
 }
 
+/// Recently handled shortcut presses, newest first (at most `limit` entries)
+#[tauri::command]
+pub async fn get_shortcut_activity(
+    limit: Option<usize>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<Vec<ShortcutActivation>, String> {
+    let activity = shortcut_state.activity.lock().unwrap();
+    Ok(activity
+        .iter()
+        .rev()
+        .take(limit.unwrap_or(MAX_SHORTCUT_ACTIVITY))
+        .cloned()
+        .collect())
+}
+
+/// Checks a shortcut string without binding it, so the settings UI can show
+/// inline errors such as "Unknown key: Foo"
+#[tauri::command]
+pub async fn validate_shortcut(shortcut: String) -> Result<(), String> {
+    parse_shortcut(&shortcut).map(|_| ())
+}
+
+/// Bindings skipped in the last registration pass because they collide with an
+/// earlier binding for the same shortcut, as human-readable descriptions
+#[tauri::command]
+pub async fn get_shortcut_conflicts(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<Vec<String>, String> {
+    Ok(shortcut_state.conflicts.lock().unwrap().clone())
+}
+
+/// Names of every overlay action accepted by invoke_overlay_action
+#[tauri::command]
+pub async fn list_overlay_actions() -> Result<Vec<String>, String> {
+    Ok(OVERLAY_ACTIONS.iter().map(ShortcutAction::name).collect())
+}
+
+/// Runs an overlay action by name exactly as its hotkey would, for assistive
+/// tech and macro tools that can't rely on global shortcuts
+#[tauri::command]
+pub async fn invoke_overlay_action(action: String, app_handle: AppHandle) -> Result<(), String> {
+    overlay::ensure_overlay_enabled(&app_handle)?;
+
+    let shortcut_action = OVERLAY_ACTIONS
+        .iter()
+        .find(|candidate| candidate.name() == action)
+        .ok_or_else(|| format!("Unknown overlay action: {}", action))?;
+
+    log::info!("Invoking overlay action {}", action);
+    run_shortcut_action(&app_handle, shortcut_action);
+
+    Ok(())
+}
+
+/// Per-binding result of the last registration pass, including bindings that never
+/// reached registration because they are disabled or could not be parsed
+#[tauri::command]
+pub async fn get_shortcut_status(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<Vec<ShortcutStatus>, String> {
+    Ok(shortcut_state.status.lock().unwrap().clone())
+}
+
+/// Returns the shortcut config with every binding in canonical form,
+/// or every validation problem found
+#[tauri::command]
+pub async fn get_normalized_shortcut_config(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<UnifiedShortcutConfig, Vec<String>> {
+    let config = shortcut_state.config.lock().unwrap().shortcuts.clone();
+    normalize_shortcut_config(&config)
+}
+
+/// Reports how `config` would register if saved, without touching disk or the
+/// live registration
+#[tauri::command]
+pub async fn preview_shortcut_config(
+    config: UnifiedShortcutConfig,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<ShortcutPreviewReport, String> {
+    let overlay_enabled = shortcut_state.config.lock().unwrap().overlay_enabled;
+    let report = preview_bindings(&config, overlay_enabled);
+
+    log::info!(
+        "Previewed shortcut config: {} binding(s), clean: {}",
+        report.bindings.len(),
+        report.clean
+    );
+    Ok(report)
+}
+
+/// Whether settings are locked by an administrator, so the UI can disable editing
+#[tauri::command]
+pub async fn get_config_locked(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<bool, String> {
+    Ok(is_config_locked(&shortcut_state.config.lock().unwrap()))
+}
+
+/// Saves the shortcut config and re-registers the shortcuts right away
 #[tauri::command]
 pub async fn set_shortcut_config(
     config: UnifiedShortcutConfig,
@@ -117,24 +917,324 @@ pub async fn set_shortcut_config(
 ) -> Result<(), String> {
     log::info!("Setting unified shortcut config");
 
-    // Preserve ollama_url from current config
-    let ollama_url = shortcut_state.config.lock().unwrap().ollama_url.clone();
+    store_shortcut_config(&settings_path(&app_handle)?, &shortcut_state, config)?;
+    refresh_shortcuts(&app_handle)?;
 
-    let new_app_config = AppConfig {
-        shortcuts: config,
-        ollama_url,
+    log::info!("Shortcut config saved and applied");
+    Ok(())
+}
+
+// Only the shortcuts change; ollama_url, ollama_api_key and every other
+// setting are kept from the current config. Agents sent as a bare key keep
+// their app condition, which is managed by set_agent_shortcut_condition
+fn store_shortcut_config(
+    settings_path: &std::path::Path,
+    shortcut_state: &UnifiedShortcutState,
+    mut config: UnifiedShortcutConfig,
+) -> Result<(), String> {
+    update_config_at(settings_path, shortcut_state, |app_config| {
+        keep_agent_conditions(&mut config, &app_config.shortcuts);
+        app_config.shortcuts = config;
+    })
+}
+
+/// Gives agents in `config` that are still bound but carry no app condition
+/// the one they have in `current`
+pub fn keep_agent_conditions(config: &mut UnifiedShortcutConfig, current: &UnifiedShortcutConfig) {
+    for (agent_id, shortcut) in config.agent_shortcuts.iter_mut() {
+        if shortcut.active_when_app.is_none() {
+            shortcut.active_when_app = current
+                .agent_shortcuts
+                .get(agent_id)
+                .and_then(|current| current.active_when_app.clone());
+        }
+    }
+}
+
+/// Binds `new_key` to a single action (None unbinds it), re-registers the
+/// shortcuts and returns the key that was previously bound
+#[tauri::command]
+pub async fn rebind_shortcut(
+    action: String,
+    new_key: Option<String>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<Option<String>, String> {
+    log::info!("Rebinding shortcut '{}' to {:?}", action, new_key);
+
+    let new_key = match new_key.filter(|key| !key.is_empty()) {
+        Some(key) => Some(normalize_shortcut(&key)?),
+        None => None,
     };
 
-    // Save to disk
-    save_config_to_disk(&app_handle, &new_app_config)?;
+    let mut shortcuts = shortcut_state.config.lock().unwrap().shortcuts.clone();
+
+    // Reject keys that would shadow another action's binding
+    if let Some(new_shortcut) = new_key.as_deref().and_then(parse_shortcut_string) {
+        let conflict = shortcuts.all_bindings().into_iter().find(|(other, key)| {
+            *other != action && parse_shortcut_string(key) == Some(new_shortcut)
+        });
+        if let Some((other, key)) = conflict {
+            return Err(format!("{} is already bound to {}", key, other));
+        }
+    }
+
+    let previous = shortcuts.set_binding(&action, new_key)?;
+
+    update_config(&app_handle, &shortcut_state, |config| {
+        config.shortcuts = shortcuts;
+    })?;
+    refresh_shortcuts(&app_handle)?;
+
+    Ok(previous)
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ShortcutChange {
+    action: String,
+    // None (or empty) unbinds the action
+    key: Option<String>,
+}
+
+/// Applies several rebinds at once: the resulting set is validated as a whole,
+/// persisted once and registered in a single pass. Nothing changes on error
+#[tauri::command]
+pub async fn apply_shortcut_changes(
+    changes: Vec<ShortcutChange>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Applying {} shortcut change(s)", changes.len());
+
+    let (mut shortcuts, overlay_enabled) = {
+        let config = shortcut_state.config.lock().unwrap();
+        (config.shortcuts.clone(), config.overlay_enabled)
+    };
+
+    for change in changes {
+        let key = match change.key.filter(|key| !key.is_empty()) {
+            Some(key) => {
+                Some(normalize_shortcut(&key).map_err(|e| format!("{}: {}", change.action, e))?)
+            }
+            None => None,
+        };
+        shortcuts.set_binding(&change.action, key)?;
+    }
 
-    // Update in-memory config
-    *shortcut_state.config.lock().unwrap() = new_app_config;
+    let problems: Vec<String> = preview_bindings(&shortcuts, overlay_enabled)
+        .bindings
+        .into_iter()
+        .filter_map(|preview| {
+            preview
+                .message
+                .filter(|_| preview.outcome != ShortcutPreviewOutcome::Disabled)
+                .map(|message| format!("{} ({}): {}", preview.action, preview.key, message))
+        })
+        .collect();
+    if !problems.is_empty() {
+        return Err(problems.join("; "));
+    }
+
+    update_config(&app_handle, &shortcut_state, |config| {
+        config.shortcuts = shortcuts;
+    })?;
+    refresh_shortcuts(&app_handle)
+}
+
+/// Removes one agent's shortcut and re-registers the shortcuts, returning the key
+/// it was bound to; errors when the agent has no shortcut
+#[tauri::command]
+pub async fn remove_agent_shortcut(
+    agent_id: String,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let key = shortcut_state
+        .config
+        .lock()
+        .unwrap()
+        .shortcuts
+        .agent_shortcuts
+        .get(&agent_id)
+        .map(|shortcut| shortcut.key.clone())
+        .ok_or_else(|| format!("Agent '{}' has no shortcut", agent_id))?;
 
-    log::info!("Shortcut config saved. Application restart required for changes to take effect.");
+    log::info!("Removing shortcut {} for agent '{}'", key, agent_id);
+    update_config(&app_handle, &shortcut_state, |config| {
+        config.shortcuts.agent_shortcuts.remove(&agent_id);
+    })?;
+    refresh_shortcuts(&app_handle)?;
+
+    Ok(key)
+}
+
+/// Limits an agent's shortcut to when the focused window's title contains `app`
+/// (None removes the condition; persisted). Best effort: the global shortcut
+/// stays registered, so the OS still consumes the key in other apps rather than
+/// passing it through, and the check needs X11 on Linux
+#[tauri::command]
+pub async fn set_agent_shortcut_condition(
+    agent_id: String,
+    app: Option<String>,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let app = app
+        .map(|app| app.trim().to_string())
+        .filter(|app| !app.is_empty());
+    log::info!(
+        "Setting shortcut condition for agent '{}' to {:?}",
+        agent_id,
+        app
+    );
+
+    let mut shortcuts = shortcut_state.config.lock().unwrap().shortcuts.clone();
+    shortcuts
+        .agent_shortcuts
+        .get_mut(&agent_id)
+        .ok_or_else(|| format!("Agent '{}' has no shortcut", agent_id))?
+        .active_when_app = app;
+
+    update_config(&app_handle, &shortcut_state, |config| {
+        config.shortcuts = shortcuts;
+    })
+}
+
+/// Restores the platform default shortcuts, leaving every other setting untouched
+#[tauri::command]
+pub async fn reset_shortcuts_to_default(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<UnifiedShortcutConfig, String> {
+    log::info!("Resetting shortcuts to platform defaults");
+
+    let defaults = UnifiedShortcutConfig::default();
+    update_config(&app_handle, &shortcut_state, |config| {
+        config.shortcuts = defaults.clone();
+    })?;
+    refresh_shortcuts(&app_handle)?;
+
+    Ok(defaults)
+}
+
+/// Temporarily releases every global shortcut without touching the config, e.g.
+/// to rule Observer out when troubleshooting conflicts with other apps.
+/// reregister_shortcuts or any shortcut config change brings them back
+#[tauri::command]
+pub async fn unregister_all_shortcuts(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    #[cfg(desktop)]
+    {
+        use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+        app_handle
+            .global_shortcut()
+            .unregister_all()
+            .map_err(|e| format!("Failed to unregister shortcuts: {}", e))?;
+    }
+    #[cfg(not(desktop))]
+    let _ = app_handle;
+
+    shortcut_state.registered_shortcuts.lock().unwrap().clear();
+    shortcut_state.bindings.lock().unwrap().clear();
+    shortcut_state.status.lock().unwrap().clear();
+    shortcut_state.conflicts.lock().unwrap().clear();
+
+    log::info!("All global shortcuts unregistered");
     Ok(())
 }
 
+/// Registers the shortcuts from the in-memory config again
+#[tauri::command]
+pub async fn reregister_shortcuts(app_handle: AppHandle) -> Result<(), String> {
+    log::info!("Re-registering global shortcuts");
+    refresh_shortcuts(&app_handle)
+}
+
+// Readable label for an overlay binding name, e.g. "overlay_move_up" -> "Move up"
+fn overlay_display_name(name: &str) -> String {
+    let label = name.trim_start_matches("overlay_").replace('_', " ");
+    let mut chars = label.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => label,
+    }
+}
+
+/// Renders the configured shortcuts grouped by category as "markdown" or "text"
+#[tauri::command]
+pub async fn export_shortcuts_cheatsheet(
+    format: String,
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<String, String> {
+    let markdown = match format.as_str() {
+        "markdown" => true,
+        "text" => false,
+        other => return Err(format!("Unknown cheat sheet format: {}", other)),
+    };
+    let config = shortcut_state.config.lock().unwrap().shortcuts.clone();
+
+    let overlay: Vec<(String, String)> = config
+        .overlay_bindings()
+        .into_iter()
+        .filter_map(|(name, key)| {
+            key.as_ref()
+                .filter(|key| !key.is_empty())
+                .map(|key| (overlay_display_name(name), key.clone()))
+        })
+        .collect();
+    let mut agents: Vec<(String, String)> = config
+        .agent_shortcuts
+        .iter()
+        .filter(|(_, shortcut)| !shortcut.key.is_empty())
+        .map(|(agent_id, shortcut)| (format!("Toggle {}", agent_id), shortcut.key.clone()))
+        .collect();
+    agents.sort();
+    let mut broadcasts: Vec<(String, String)> = config
+        .agent_broadcast_shortcuts
+        .iter()
+        .filter(|(_, key)| !key.is_empty())
+        .map(|(action, key)| (format!("{} all agents", action), key.clone()))
+        .collect();
+    broadcasts.sort();
+
+    let mut sheet = String::new();
+    if markdown {
+        sheet.push_str("# Observer Shortcuts\n");
+    } else {
+        sheet.push_str("OBSERVER SHORTCUTS\n");
+    }
+
+    for (title, entries) in [
+        ("Overlay", overlay),
+        ("Agents", agents),
+        ("Broadcast", broadcasts),
+    ] {
+        if entries.is_empty() {
+            continue;
+        }
+        if markdown {
+            sheet.push_str(&format!(
+                "\n## {}\n\n| Shortcut | Action |\n| --- | --- |\n",
+                title
+            ));
+            for (label, key) in entries {
+                sheet.push_str(&format!("| `{}` | {} |\n", key, label));
+            }
+        } else {
+            sheet.push_str(&format!("\n{}\n", title));
+            let width = entries.iter().map(|(_, key)| key.len()).max().unwrap_or(0);
+            for (label, key) in entries {
+                sheet.push_str(&format!("  {:<width$}  {}\n", key, label, width = width));
+            }
+        }
+    }
+
+    Ok(sheet)
+}
+
 // Settings.json management
 fn get_settings_path(
     app_handle: &AppHandle,
@@ -165,6 +1265,7 @@ pub fn load_config_from_disk(app_handle: &AppHandle) -> AppConfig {
                                         let new_config = AppConfig {
                                             shortcuts: old_config,
                                             ollama_url: None,
+                                            ..AppConfig::default()
                                         };
                                         // Save the migrated config in new format
                                         if let Err(e) = save_config_to_disk(app_handle, &new_config)
@@ -202,39 +1303,66 @@ pub fn load_config_from_disk(app_handle: &AppHandle) -> AppConfig {
 }
 
 fn save_config_to_disk(app_handle: &AppHandle, config: &AppConfig) -> Result<(), String> {
-    match get_settings_path(app_handle) {
-        Ok(settings_path) => match serde_json::to_string_pretty(config) {
-            Ok(json_content) => match std::fs::write(&settings_path, json_content) {
-                Ok(_) => {
-                    log::info!("Saved app config to {:?}", settings_path);
-                    Ok(())
-                }
-                Err(e) => {
-                    let error_msg = format!("Failed to write settings.json: {}", e);
-                    log::error!("{}", error_msg);
-                    Err(error_msg)
-                }
-            },
+    write_config(&settings_path(app_handle)?, config)
+}
+
+fn settings_path(app_handle: &AppHandle) -> Result<std::path::PathBuf, String> {
+    get_settings_path(app_handle).map_err(|e| {
+        let error_msg = format!("Failed to get settings path: {}", e);
+        log::error!("{}", error_msg);
+        error_msg
+    })
+}
+
+fn write_config(settings_path: &std::path::Path, config: &AppConfig) -> Result<(), String> {
+    match serde_json::to_string_pretty(config) {
+        Ok(json_content) => match std::fs::write(settings_path, json_content) {
+            Ok(_) => {
+                log::info!("Saved app config to {:?}", settings_path);
+                Ok(())
+            }
             Err(e) => {
-                let error_msg = format!("Failed to serialize config: {}", e);
+                let error_msg = format!("Failed to write settings.json: {}", e);
                 log::error!("{}", error_msg);
                 Err(error_msg)
             }
         },
         Err(e) => {
-            let error_msg = format!("Failed to get settings path: {}", e);
+            let error_msg = format!("Failed to serialize config: {}", e);
             log::error!("{}", error_msg);
             Err(error_msg)
         }
     }
 }
 
+// Environment override for managed deployments that can't ship a settings.json
+const CONFIG_LOCKED_ENV: &str = "OBSERVER_CONFIG_LOCKED";
+
+/// Whether the config is locked by an administrator (settings.json or env var)
+pub fn is_config_locked(config: &AppConfig) -> bool {
+    config.config_locked
+        || std::env::var(CONFIG_LOCKED_ENV)
+            .is_ok_and(|value| matches!(value.trim(), "1" | "true" | "yes"))
+}
+
+/// Fails when the config is locked. Commands that also change live state (e.g.
+/// the overlay window) call it before doing so, so a locked config changes nothing
+pub fn ensure_config_unlocked(shortcut_state: &UnifiedShortcutState) -> Result<(), String> {
+    if is_config_locked(&shortcut_state.config.lock().unwrap()) {
+        Err("Configuration is locked by administrator".to_string())
+    } else {
+        Ok(())
+    }
+}
+
 // Helper function to save ollama URL while preserving shortcuts
 pub fn save_ollama_url(
     app_handle: &AppHandle,
     shortcut_state: &State<UnifiedShortcutState>,
     ollama_url: Option<String>,
 ) -> Result<(), String> {
+    ensure_config_unlocked(shortcut_state)?;
+
     // Get current config and update ollama_url
     let mut app_config = shortcut_state.config.lock().unwrap().clone();
     app_config.ollama_url = ollama_url;
@@ -248,13 +1376,64 @@ pub fn save_ollama_url(
     Ok(())
 }
 
-// Shortcut parsing
-fn parse_shortcut_string(shortcut_str: &str) -> Option<tauri_plugin_global_shortcut::Shortcut> {
+// Helper function to apply an arbitrary change to the config and persist it
+pub fn update_config<F>(
+    app_handle: &AppHandle,
+    shortcut_state: &UnifiedShortcutState,
+    update: F,
+) -> Result<(), String>
+where
+    F: FnOnce(&mut AppConfig),
+{
+    update_config_at(&settings_path(app_handle)?, shortcut_state, update)
+}
+
+// update_config against an explicit settings.json, so it can run without an app
+fn update_config_at<F>(
+    settings_path: &std::path::Path,
+    shortcut_state: &UnifiedShortcutState,
+    update: F,
+) -> Result<(), String>
+where
+    F: FnOnce(&mut AppConfig),
+{
+    ensure_config_unlocked(shortcut_state)?;
+
+    let mut app_config = shortcut_state.config.lock().unwrap().clone();
+    update(&mut app_config);
+    // Only the provisioned settings.json can lock the config
+    app_config.config_locked = false;
+
+    // Save to disk
+    write_config(settings_path, &app_config)?;
+
+    // Update in-memory state
+    *shortcut_state.config.lock().unwrap() = app_config;
+
+    Ok(())
+}
+
+// "CmdOrCtrl" resolves to the platform's primary modifier, so one settings.json
+// works on macOS and Windows/Linux alike
+#[cfg(target_os = "macos")]
+const CMD_OR_CTRL: tauri_plugin_global_shortcut::Modifiers =
+    tauri_plugin_global_shortcut::Modifiers::SUPER;
+#[cfg(not(target_os = "macos"))]
+const CMD_OR_CTRL: tauri_plugin_global_shortcut::Modifiers =
+    tauri_plugin_global_shortcut::Modifiers::CONTROL;
+
+// Shortcut parsing; None for anything parse_shortcut rejects
+pub fn parse_shortcut_string(shortcut_str: &str) -> Option<tauri_plugin_global_shortcut::Shortcut> {
+    parse_shortcut(shortcut_str).ok()
+}
+
+// Parses a shortcut string, naming the token that isn't recognized on failure
+fn parse_shortcut(shortcut_str: &str) -> Result<tauri_plugin_global_shortcut::Shortcut, String> {
     use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut};
 
     let parts: Vec<&str> = shortcut_str.split('+').map(|s| s.trim()).collect();
-    if parts.is_empty() {
-        return None;
+    if parts.iter().all(|part| part.is_empty()) {
+        return Err(format!("Empty shortcut: '{}'", shortcut_str));
     }
 
     let mut modifiers = Modifiers::empty();
@@ -262,13 +1441,14 @@ fn parse_shortcut_string(shortcut_str: &str) -> Option<tauri_plugin_global_short
         parts[0]
     } else {
         // Parse modifiers
-        for i in 0..parts.len() - 1 {
-            match parts[i] {
+        for part in &parts[..parts.len() - 1] {
+            match *part {
+                "CmdOrCtrl" => modifiers |= CMD_OR_CTRL,
                 "Cmd" | "Super" => modifiers |= Modifiers::SUPER,
                 "Alt" => modifiers |= Modifiers::ALT,
                 "Ctrl" => modifiers |= Modifiers::CONTROL,
                 "Shift" => modifiers |= Modifiers::SHIFT,
-                _ => return None,
+                other => return Err(format!("Unknown modifier: {}", other)),
             }
         }
         parts[parts.len() - 1]
@@ -315,6 +1495,24 @@ fn parse_shortcut_string(shortcut_str: &str) -> Option<tauri_plugin_global_short
         "8" => Code::Digit8,
         "9" => Code::Digit9,
 
+        // Numpad keys
+        "Numpad0" => Code::Numpad0,
+        "Numpad1" => Code::Numpad1,
+        "Numpad2" => Code::Numpad2,
+        "Numpad3" => Code::Numpad3,
+        "Numpad4" => Code::Numpad4,
+        "Numpad5" => Code::Numpad5,
+        "Numpad6" => Code::Numpad6,
+        "Numpad7" => Code::Numpad7,
+        "Numpad8" => Code::Numpad8,
+        "Numpad9" => Code::Numpad9,
+        "NumpadAdd" => Code::NumpadAdd,
+        "NumpadSubtract" => Code::NumpadSubtract,
+        "NumpadMultiply" => Code::NumpadMultiply,
+        "NumpadDivide" => Code::NumpadDivide,
+        "NumpadEnter" => Code::NumpadEnter,
+        "NumpadDecimal" => Code::NumpadDecimal,
+
         // Function keys
         "F1" => Code::F1,
         "F2" => Code::F2,
@@ -328,6 +1526,18 @@ fn parse_shortcut_string(shortcut_str: &str) -> Option<tauri_plugin_global_short
         "F10" => Code::F10,
         "F11" => Code::F11,
         "F12" => Code::F12,
+        "F13" => Code::F13,
+        "F14" => Code::F14,
+        "F15" => Code::F15,
+        "F16" => Code::F16,
+        "F17" => Code::F17,
+        "F18" => Code::F18,
+        "F19" => Code::F19,
+        "F20" => Code::F20,
+        "F21" => Code::F21,
+        "F22" => Code::F22,
+        "F23" => Code::F23,
+        "F24" => Code::F24,
 
         // Arrow keys
         "ArrowUp" => Code::ArrowUp,
@@ -347,314 +1557,595 @@ fn parse_shortcut_string(shortcut_str: &str) -> Option<tauri_plugin_global_short
         "PageUp" => Code::PageUp,
         "PageDown" => Code::PageDown,
 
-        _ => return None,
+        // Punctuation keys, by name or as the literal symbol (US layout)
+        "Comma" | "," => Code::Comma,
+        "Period" | "." => Code::Period,
+        "Slash" | "/" => Code::Slash,
+        "Backslash" | "\\" => Code::Backslash,
+        "Semicolon" | ";" => Code::Semicolon,
+        "Quote" | "'" => Code::Quote,
+        "BracketLeft" | "[" => Code::BracketLeft,
+        "BracketRight" | "]" => Code::BracketRight,
+        "Minus" | "-" => Code::Minus,
+        "Equal" | "=" => Code::Equal,
+        "Backquote" | "`" => Code::Backquote,
+
+        // Media keys, usually bound without modifiers. Some OSes (macOS in
+        // particular) route these to the system media controls before the app
+        // ever sees them, so registration can succeed without the shortcut firing
+        "MediaPlayPause" => Code::MediaPlayPause,
+        "MediaStop" => Code::MediaStop,
+        "MediaTrackNext" => Code::MediaTrackNext,
+        "MediaTrackPrevious" => Code::MediaTrackPrevious,
+        "AudioVolumeUp" => Code::AudioVolumeUp,
+        "AudioVolumeDown" => Code::AudioVolumeDown,
+        "AudioVolumeMute" => Code::AudioVolumeMute,
+
+        other => return Err(format!("Unknown key: {}", other)),
     };
 
-    Some(Shortcut::new(Some(modifiers), key))
+    Ok(Shortcut::new(Some(modifiers), key))
 }
 
-// Helper function to ensure overlay always ignores cursor events
-fn ensure_overlay_click_through(window: &tauri::WebviewWindow) {
-    if let Err(e) = window.set_ignore_cursor_events(true) {
-        log::warn!("Failed to re-enable click-through on overlay: {}", e);
+/// Canonical form of a shortcut string: known modifiers in a fixed order
+/// (CmdOrCtrl, Ctrl, Alt, Shift, Cmd) followed by the key, e.g. " Shift + Alt+B" -> "Alt+Shift+B".
+/// CmdOrCtrl is kept as written so the result stays portable across platforms
+pub fn normalize_shortcut(shortcut_str: &str) -> Result<String, String> {
+    let parts: Vec<&str> = shortcut_str.split('+').map(|s| s.trim()).collect();
+    let (key_part, modifier_parts) = match parts.split_last() {
+        Some((key, modifiers)) if !key.is_empty() => (*key, modifiers),
+        _ => return Err(format!("Empty shortcut: '{}'", shortcut_str)),
+    };
+
+    let mut cmd_or_ctrl = false;
+    let mut ctrl = false;
+    let mut alt = false;
+    let mut shift = false;
+    let mut cmd = false;
+    for modifier in modifier_parts {
+        let flag = match *modifier {
+            "CmdOrCtrl" => &mut cmd_or_ctrl,
+            "Ctrl" => &mut ctrl,
+            "Alt" => &mut alt,
+            "Shift" => &mut shift,
+            "Cmd" | "Super" => &mut cmd,
+            other => return Err(format!("Unknown modifier: {}", other)),
+        };
+        if *flag {
+            return Err(format!("Duplicate modifier: {}", modifier));
+        }
+        *flag = true;
     }
-}
 
-// Main registration function - called ONLY at startup
-#[cfg(desktop)]
-pub fn register_shortcuts_on_startup(
-    app: &mut tauri::App,
-) -> Result<(), Box<dyn std::error::Error>> {
-    use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+    if parse_shortcut_string(key_part).is_none() {
+        return Err(format!("Unknown key: {}", key_part));
+    }
 
-    let shortcut_state = app.state::<UnifiedShortcutState>();
-    let app_config = shortcut_state.config.lock().unwrap().clone();
-    let config = app_config.shortcuts;
+    let mut normalized: Vec<&str> = Vec::new();
+    if cmd_or_ctrl {
+        normalized.push("CmdOrCtrl");
+    }
+    if ctrl {
+        normalized.push("Ctrl");
+    }
+    if alt {
+        normalized.push("Alt");
+    }
+    if shift {
+        normalized.push("Shift");
+    }
+    if cmd {
+        normalized.push("Cmd");
+    }
+    normalized.push(key_part);
 
-    // Collect all shortcuts with their actions
-    let mut shortcuts_to_register: Vec<(
-        tauri_plugin_global_shortcut::Shortcut,
-        String,
-        ShortcutAction,
-    )> = Vec::new();
+    Ok(normalized.join("+"))
+}
 
-    // Overlay shortcuts
-    if let Some(key) = &config.overlay_toggle {
-        if let Some(shortcut) = parse_shortcut_string(key) {
-            shortcuts_to_register.push((shortcut, key.clone(), ShortcutAction::OverlayToggle));
+/// Normalizes every binding in the config, collecting all errors rather than stopping at the first
+pub fn normalize_shortcut_config(
+    config: &UnifiedShortcutConfig,
+) -> Result<UnifiedShortcutConfig, Vec<String>> {
+    let mut normalized = config.clone();
+    let mut errors = Vec::new();
+
+    for (name, binding) in normalized.overlay_bindings_mut() {
+        if let Some(key) = binding.as_mut().filter(|key| !key.is_empty()) {
+            match normalize_shortcut(key) {
+                Ok(normalized_key) => *key = normalized_key,
+                Err(e) => errors.push(format!("{}: {}", name, e)),
+            }
         }
     }
 
-    if let Some(key) = &config.overlay_move_up {
-        if let Some(shortcut) = parse_shortcut_string(key) {
-            shortcuts_to_register.push((shortcut, key.clone(), ShortcutAction::OverlayMoveUp));
+    for (agent_id, shortcut) in normalized.agent_shortcuts.iter_mut() {
+        let key = &mut shortcut.key;
+        if !key.is_empty() {
+            match normalize_shortcut(key) {
+                Ok(normalized_key) => *key = normalized_key,
+                Err(e) => errors.push(format!("agent '{}': {}", agent_id, e)),
+            }
         }
     }
 
-    if let Some(key) = &config.overlay_move_down {
-        if let Some(shortcut) = parse_shortcut_string(key) {
-            shortcuts_to_register.push((shortcut, key.clone(), ShortcutAction::OverlayMoveDown));
+    for (action, key) in normalized.agent_broadcast_shortcuts.iter_mut() {
+        if !key.is_empty() {
+            match normalize_shortcut(key) {
+                Ok(normalized_key) => *key = normalized_key,
+                Err(e) => errors.push(format!("broadcast '{}': {}", action, e)),
+            }
         }
     }
 
-    if let Some(key) = &config.overlay_move_left {
-        if let Some(shortcut) = parse_shortcut_string(key) {
-            shortcuts_to_register.push((shortcut, key.clone(), ShortcutAction::OverlayMoveLeft));
-        }
+    if errors.is_empty() {
+        Ok(normalized)
+    } else {
+        Err(errors)
     }
+}
+
+// A parsed shortcut together with its config key string and action
+#[derive(Debug, Clone)]
+struct ShortcutBinding {
+    shortcut: tauri_plugin_global_shortcut::Shortcut,
+    key: String,
+    action: ShortcutAction,
+}
 
-    if let Some(key) = &config.overlay_move_right {
+// Collect all shortcuts with their actions
+fn collect_bindings(app_config: &AppConfig) -> Vec<ShortcutBinding> {
+    let config = &app_config.shortcuts;
+    let mut bindings = Vec::new();
+
+    let mut push = |key: &str, action: ShortcutAction| {
+        if key.is_empty() {
+            return;
+        }
         if let Some(shortcut) = parse_shortcut_string(key) {
-            shortcuts_to_register.push((shortcut, key.clone(), ShortcutAction::OverlayMoveRight));
+            bindings.push(ShortcutBinding {
+                shortcut,
+                key: key.to_string(),
+                action,
+            });
         }
-    }
+    };
 
-    if let Some(key) = &config.overlay_resize_up {
-        if let Some(shortcut) = parse_shortcut_string(key) {
-            shortcuts_to_register.push((shortcut, key.clone(), ShortcutAction::OverlayResizeUp));
+    // Overlay shortcuts (skipped entirely when the overlay is disabled)
+    if app_config.overlay_enabled {
+        let overlay_actions = [
+            (&config.overlay_toggle, ShortcutAction::OverlayToggle),
+            (&config.overlay_move_up, ShortcutAction::OverlayMoveUp),
+            (&config.overlay_move_down, ShortcutAction::OverlayMoveDown),
+            (&config.overlay_move_left, ShortcutAction::OverlayMoveLeft),
+            (&config.overlay_move_right, ShortcutAction::OverlayMoveRight),
+            (
+                &config.overlay_move_up_left,
+                ShortcutAction::OverlayMoveUpLeft,
+            ),
+            (
+                &config.overlay_move_up_right,
+                ShortcutAction::OverlayMoveUpRight,
+            ),
+            (
+                &config.overlay_move_down_left,
+                ShortcutAction::OverlayMoveDownLeft,
+            ),
+            (
+                &config.overlay_move_down_right,
+                ShortcutAction::OverlayMoveDownRight,
+            ),
+            (&config.overlay_resize_up, ShortcutAction::OverlayResizeUp),
+            (
+                &config.overlay_resize_down,
+                ShortcutAction::OverlayResizeDown,
+            ),
+            (
+                &config.overlay_resize_left,
+                ShortcutAction::OverlayResizeLeft,
+            ),
+            (
+                &config.overlay_resize_right,
+                ShortcutAction::OverlayResizeRight,
+            ),
+            (&config.overlay_clear, ShortcutAction::OverlayClear),
+            (&config.overlay_reset, ShortcutAction::OverlayReset),
+            (&config.overlay_center, ShortcutAction::OverlayCenter),
+            (
+                &config.overlay_snap_top,
+                ShortcutAction::OverlaySnap(overlay::SnapEdge::Top),
+            ),
+            (
+                &config.overlay_snap_bottom,
+                ShortcutAction::OverlaySnap(overlay::SnapEdge::Bottom),
+            ),
+            (
+                &config.overlay_snap_left,
+                ShortcutAction::OverlaySnap(overlay::SnapEdge::Left),
+            ),
+            (
+                &config.overlay_snap_right,
+                ShortcutAction::OverlaySnap(overlay::SnapEdge::Right),
+            ),
+        ];
+        for (key, action) in overlay_actions {
+            if let Some(key) = key {
+                push(key, action);
+            }
         }
+    } else {
+        log::info!("Overlay disabled - skipping overlay shortcuts");
     }
 
-    if let Some(key) = &config.overlay_resize_down {
-        if let Some(shortcut) = parse_shortcut_string(key) {
-            shortcuts_to_register.push((shortcut, key.clone(), ShortcutAction::OverlayResizeDown));
-        }
+    // Agent shortcuts
+    for (agent_id, shortcut) in &config.agent_shortcuts {
+        push(&shortcut.key, ShortcutAction::AgentToggle(agent_id.clone()));
     }
 
-    if let Some(key) = &config.overlay_resize_left {
-        if let Some(shortcut) = parse_shortcut_string(key) {
-            shortcuts_to_register.push((shortcut, key.clone(), ShortcutAction::OverlayResizeLeft));
-        }
+    // Broadcast shortcuts
+    for (action, shortcut_key) in &config.agent_broadcast_shortcuts {
+        push(shortcut_key, ShortcutAction::AgentBroadcast(action.clone()));
     }
 
-    if let Some(key) = &config.overlay_resize_right {
-        if let Some(shortcut) = parse_shortcut_string(key) {
-            shortcuts_to_register.push((shortcut, key.clone(), ShortcutAction::OverlayResizeRight));
+    bindings
+}
+
+fn toggle_agent(app_handle: &AppHandle, agent_id: &str) {
+    crate::commands::broadcast_command(
+        app_handle,
+        crate::commands::DEFAULT_MESSAGE_TYPE.to_string(),
+        agent_id.to_string(),
+        "toggle".to_string(),
+        None,
+    );
+}
+
+// Runs the action bound to a pressed shortcut
+fn run_shortcut_action(app_handle: &AppHandle, action: &ShortcutAction) {
+    match action {
+        ShortcutAction::OverlayToggle => {
+            if let Some(window) = app_handle.get_webview_window("overlay") {
+                match window.is_visible() {
+                    Ok(visible) => {
+                        let hide_when_empty = app_handle
+                            .state::<UnifiedShortcutState>()
+                            .config
+                            .lock()
+                            .unwrap()
+                            .overlay_hide_when_empty;
+                        if !visible
+                            && hide_when_empty
+                            && app_handle
+                                .state::<crate::OverlayState>()
+                                .messages
+                                .lock()
+                                .unwrap()
+                                .is_empty()
+                        {
+                            log::info!("Overlay toggle ignored: no messages to show");
+                            return;
+                        }
+
+                        match overlay::set_overlay_visible(app_handle, &window, !visible) {
+                            Ok(_) => log::info!(
+                                "Overlay {} via toggle shortcut",
+                                if visible { "hidden" } else { "shown" }
+                            ),
+                            Err(e) => log::error!(
+                                "Failed to {} overlay: {}",
+                                if visible { "hide" } else { "show" },
+                                e
+                            ),
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("Failed to check overlay visibility: {}", e)
+                    }
+                }
+            }
         }
-    }
 
-    // Agent shortcuts
-    for (agent_id, shortcut_key) in &config.agent_shortcuts {
-        if !shortcut_key.is_empty() {
-            if let Some(shortcut) = parse_shortcut_string(shortcut_key) {
-                shortcuts_to_register.push((
-                    shortcut,
-                    shortcut_key.clone(),
-                    ShortcutAction::AgentToggle(agent_id.clone()),
-                ));
+        ShortcutAction::OverlayMoveUp
+        | ShortcutAction::OverlayMoveDown
+        | ShortcutAction::OverlayMoveLeft
+        | ShortcutAction::OverlayMoveRight
+        | ShortcutAction::OverlayMoveUpLeft
+        | ShortcutAction::OverlayMoveUpRight
+        | ShortcutAction::OverlayMoveDownLeft
+        | ShortcutAction::OverlayMoveDownRight => {
+            if let Some(window) = app_handle.get_webview_window("overlay") {
+                let (dx, dy) = match action {
+                    ShortcutAction::OverlayMoveUp => (0, -50),
+                    ShortcutAction::OverlayMoveDown => (0, 50),
+                    ShortcutAction::OverlayMoveLeft => (-50, 0),
+                    ShortcutAction::OverlayMoveRight => (50, 0),
+                    ShortcutAction::OverlayMoveUpLeft => (-50, -50),
+                    ShortcutAction::OverlayMoveUpRight => (50, -50),
+                    ShortcutAction::OverlayMoveDownLeft => (-50, 50),
+                    ShortcutAction::OverlayMoveDownRight => (50, 50),
+                    _ => (0, 0),
+                };
+
+                match overlay::move_overlay_by(&window, dx, dy) {
+                    Ok((new_x, new_y)) => {
+                        let direction = match action {
+                            ShortcutAction::OverlayMoveUp => "up",
+                            ShortcutAction::OverlayMoveDown => "down",
+                            ShortcutAction::OverlayMoveLeft => "left",
+                            ShortcutAction::OverlayMoveRight => "right",
+                            ShortcutAction::OverlayMoveUpLeft => "up-left",
+                            ShortcutAction::OverlayMoveUpRight => "up-right",
+                            ShortcutAction::OverlayMoveDownLeft => "down-left",
+                            ShortcutAction::OverlayMoveDownRight => "down-right",
+                            _ => "unknown",
+                        };
+                        log::info!("Overlay moved {} to ({}, {})", direction, new_x, new_y);
+                        overlay::ensure_overlay_click_through(&window);
+                    }
+                    Err(e) => log::warn!("{}", e),
+                }
             }
         }
-    }
 
-    // Create action mapping for the handler
-    let actions: Vec<ShortcutAction> = shortcuts_to_register
-        .iter()
-        .map(|(_, _, action)| action.clone())
-        .collect();
-    let registered_shortcuts: Vec<tauri_plugin_global_shortcut::Shortcut> = shortcuts_to_register
-        .iter()
-        .map(|(s, _, _)| s.clone())
-        .collect();
-    let shortcut_keys: Vec<String> = shortcuts_to_register
-        .iter()
-        .map(|(_, key, _)| key.clone())
-        .collect();
+        ShortcutAction::OverlayResizeUp
+        | ShortcutAction::OverlayResizeDown
+        | ShortcutAction::OverlayResizeLeft
+        | ShortcutAction::OverlayResizeRight => {
+            if let Some(window) = app_handle.get_webview_window("overlay") {
+                let (dw, dh) = match action {
+                    ShortcutAction::OverlayResizeUp => (0, -50),
+                    ShortcutAction::OverlayResizeDown => (0, 50),
+                    ShortcutAction::OverlayResizeLeft => (-50, 0),
+                    ShortcutAction::OverlayResizeRight => (50, 0),
+                    _ => (0, 0),
+                };
 
-    // Register the single global shortcut handler
-    app.handle().plugin(
-        tauri_plugin_global_shortcut::Builder::new()
-            .with_handler(move |app_handle, shortcut, event| {
-                if event.state() != ShortcutState::Pressed {
-                    return;
+                match overlay::resize_overlay_by(&window, dw, dh) {
+                    Ok((new_width, new_height)) => {
+                        let direction = match action {
+                            ShortcutAction::OverlayResizeUp => "up",
+                            ShortcutAction::OverlayResizeDown => "down",
+                            ShortcutAction::OverlayResizeLeft => "left",
+                            ShortcutAction::OverlayResizeRight => "right",
+                            _ => "unknown",
+                        };
+                        log::info!(
+                            "Overlay resized {} to {}x{}",
+                            direction,
+                            new_width,
+                            new_height
+                        );
+                        overlay::ensure_overlay_click_through(&window);
+                    }
+                    Err(e) => log::warn!("{}", e),
                 }
+            }
+        }
 
-                // Find which shortcut was pressed and emit the event immediately for visual feedback
-                if let Some(index) = registered_shortcuts.iter().position(|s| s == shortcut) {
-                    let action = &actions[index];
+        ShortcutAction::OverlayClear => {
+            let overlay_state = app_handle.state::<crate::OverlayState>();
+            overlay::clear_overlay(app_handle, &overlay_state);
+        }
 
-                    // Emit shortcut-pressed event for visual feedback (before executing action)
-                    if let Some(shortcut_key) = shortcut_keys.get(index) {
-                        if let Err(e) = app_handle.emit("shortcut-pressed", shortcut_key) {
-                            log::warn!("Failed to emit shortcut-pressed event: {}", e);
-                        }
+        ShortcutAction::OverlayReset => {
+            if let Some(window) = app_handle.get_webview_window(overlay::OVERLAY_LABEL) {
+                match overlay::reset_overlay_geometry(&window) {
+                    Ok(()) => {
+                        log::info!("Overlay reset to its default position and size");
+                        overlay::ensure_overlay_click_through(&window);
                     }
+                    Err(e) => log::warn!("{}", e),
+                }
+            }
+        }
 
-                    match action {
-                        ShortcutAction::OverlayToggle => {
-                            if let Some(window) = app_handle.get_webview_window("overlay") {
-                                match window.is_visible() {
-                                    Ok(visible) => {
-                                        let result = if visible {
-                                            window.hide()
-                                        } else {
-                                            window.show()
-                                        };
-                                        match result {
-                                            Ok(_) => log::info!(
-                                                "Overlay {} via toggle shortcut",
-                                                if visible { "hidden" } else { "shown" }
-                                            ),
-                                            Err(e) => log::error!(
-                                                "Failed to {} overlay: {}",
-                                                if visible { "hide" } else { "show" },
-                                                e
-                                            ),
-                                        }
-                                    }
-                                    Err(e) => {
-                                        log::error!("Failed to check overlay visibility: {}", e)
-                                    }
-                                }
-                            }
-                        }
+        ShortcutAction::OverlayCenter => {
+            if let Some(window) = app_handle.get_webview_window(overlay::OVERLAY_LABEL) {
+                match overlay::center_overlay(&window) {
+                    Ok((new_x, new_y)) => {
+                        log::info!("Overlay centered at ({}, {})", new_x, new_y);
+                        overlay::ensure_overlay_click_through(&window);
+                    }
+                    Err(e) => log::warn!("{}", e),
+                }
+            }
+        }
 
-                        ShortcutAction::OverlayMoveUp
-                        | ShortcutAction::OverlayMoveDown
-                        | ShortcutAction::OverlayMoveLeft
-                        | ShortcutAction::OverlayMoveRight => {
-                            if let Some(window) = app_handle.get_webview_window("overlay") {
-                                if let Ok(current_pos) = window.outer_position() {
-                                    let (dx, dy) = match action {
-                                        ShortcutAction::OverlayMoveUp => (0, -50),
-                                        ShortcutAction::OverlayMoveDown => (0, 50),
-                                        ShortcutAction::OverlayMoveLeft => (-50, 0),
-                                        ShortcutAction::OverlayMoveRight => (50, 0),
-                                        _ => (0, 0),
-                                    };
-
-                                    let new_x = current_pos.x + dx;
-                                    let new_y = current_pos.y + dy;
-
-                                    if window
-                                        .set_position(tauri::Position::Physical(
-                                            tauri::PhysicalPosition { x: new_x, y: new_y },
-                                        ))
-                                        .is_ok()
-                                    {
-                                        let direction = match action {
-                                            ShortcutAction::OverlayMoveUp => "up",
-                                            ShortcutAction::OverlayMoveDown => "down",
-                                            ShortcutAction::OverlayMoveLeft => "left",
-                                            ShortcutAction::OverlayMoveRight => "right",
-                                            _ => "unknown",
-                                        };
-                                        log::info!(
-                                            "Overlay moved {} to ({}, {})",
-                                            direction,
-                                            new_x,
-                                            new_y
-                                        );
-                                        ensure_overlay_click_through(&window);
-                                    }
-                                }
-                            }
-                        }
+        ShortcutAction::OverlaySnap(edge) => {
+            if let Some(window) = app_handle.get_webview_window(overlay::OVERLAY_LABEL) {
+                match overlay::snap_overlay_to_edge(&window, *edge) {
+                    Ok((new_x, new_y)) => {
+                        log::info!(
+                            "Overlay snapped to {} edge at ({}, {})",
+                            edge.name(),
+                            new_x,
+                            new_y
+                        );
+                        overlay::ensure_overlay_click_through(&window);
+                    }
+                    Err(e) => log::warn!("{}", e),
+                }
+            }
+        }
 
-                        ShortcutAction::OverlayResizeUp
-                        | ShortcutAction::OverlayResizeDown
-                        | ShortcutAction::OverlayResizeLeft
-                        | ShortcutAction::OverlayResizeRight => {
-                            if let Some(window) = app_handle.get_webview_window("overlay") {
-                                if let Ok(current_size) = window.inner_size() {
-                                    let size_delta = 50.0;
-                                    let (new_width, new_height) = match action {
-                                        ShortcutAction::OverlayResizeUp => {
-                                            let new_h = (current_size.height as f64 - size_delta)
-                                                .max(200.0);
-                                            (current_size.width as f64, new_h)
-                                        }
-                                        ShortcutAction::OverlayResizeDown => {
-                                            let new_h = (current_size.height as f64 + size_delta)
-                                                .max(200.0);
-                                            (current_size.width as f64, new_h)
-                                        }
-                                        ShortcutAction::OverlayResizeLeft => {
-                                            let new_w =
-                                                (current_size.width as f64 - size_delta).max(200.0);
-                                            (new_w, current_size.height as f64)
-                                        }
-                                        ShortcutAction::OverlayResizeRight => {
-                                            let new_w =
-                                                (current_size.width as f64 + size_delta).max(200.0);
-                                            (new_w, current_size.height as f64)
-                                        }
-                                        _ => {
-                                            (current_size.width as f64, current_size.height as f64)
-                                        }
-                                    };
-
-                                    if window
-                                        .set_size(tauri::Size::Physical(tauri::PhysicalSize {
-                                            width: new_width as u32,
-                                            height: new_height as u32,
-                                        }))
-                                        .is_ok()
-                                    {
-                                        let direction = match action {
-                                            ShortcutAction::OverlayResizeUp => "up",
-                                            ShortcutAction::OverlayResizeDown => "down",
-                                            ShortcutAction::OverlayResizeLeft => "left",
-                                            ShortcutAction::OverlayResizeRight => "right",
-                                            _ => "unknown",
-                                        };
-                                        log::info!(
-                                            "Overlay resized {} to {}x{}",
-                                            direction,
-                                            new_width,
-                                            new_height
-                                        );
-                                        ensure_overlay_click_through(&window);
-                                    }
-                                }
+        ShortcutAction::AgentToggle(agent_id) => {
+            log::info!("Agent hotkey pressed for agent: {}", agent_id);
+            let condition = app_handle
+                .state::<UnifiedShortcutState>()
+                .config
+                .lock()
+                .unwrap()
+                .shortcuts
+                .agent_shortcuts
+                .get(agent_id)
+                .and_then(|shortcut| shortcut.active_when_app.clone());
+            match condition {
+                None => toggle_agent(app_handle, agent_id),
+                // Querying the focused window can block, so it runs off the handler
+                Some(app) => {
+                    let app_handle = app_handle.clone();
+                    let agent_id = agent_id.clone();
+                    tauri::async_runtime::spawn(async move {
+                        match crate::controls::active_window_title().await {
+                            Ok(title) if title.to_lowercase().contains(&app.to_lowercase()) => {
+                                toggle_agent(&app_handle, &agent_id)
                             }
+                            Ok(title) => log::info!(
+                                "Agent hotkey for {} ignored: focused window '{}' is not {}",
+                                agent_id,
+                                title,
+                                app
+                            ),
+                            Err(e) => log::warn!(
+                                "Agent hotkey for {} ignored: can't check the focused app: {}",
+                                agent_id,
+                                e
+                            ),
                         }
-
-                        ShortcutAction::AgentToggle(agent_id) => {
-                            log::info!("Agent hotkey pressed for agent: {}", agent_id);
-                            let command_state = app_handle.state::<CommandState>();
-                            crate::commands::broadcast_command(
-                                &command_state,
-                                agent_id.clone(),
-                                "toggle".to_string(),
-                            );
-                        }
-                    }
+                    });
                 }
-            })
-            .build(),
-    )?;
+            }
+        }
+
+        ShortcutAction::AgentBroadcast(broadcast_action) => {
+            log::info!("Broadcast hotkey pressed for action: {}", broadcast_action);
+            crate::commands::broadcast_command(
+                app_handle,
+                crate::commands::DEFAULT_MESSAGE_TYPE.to_string(),
+                crate::commands::BROADCAST_AGENT_ID.to_string(),
+                broadcast_action.clone(),
+                None,
+            );
+        }
+    }
+}
+
+// Human-readable description used in logs and get_registered_shortcuts
+fn describe_binding(binding: &ShortcutBinding) -> String {
+    let description = match &binding.action {
+        ShortcutAction::OverlayToggle => "overlay toggle",
+        ShortcutAction::OverlayMoveUp => "overlay move up",
+        ShortcutAction::OverlayMoveDown => "overlay move down",
+        ShortcutAction::OverlayMoveLeft => "overlay move left",
+        ShortcutAction::OverlayMoveRight => "overlay move right",
+        ShortcutAction::OverlayMoveUpLeft => "overlay move up-left",
+        ShortcutAction::OverlayMoveUpRight => "overlay move up-right",
+        ShortcutAction::OverlayMoveDownLeft => "overlay move down-left",
+        ShortcutAction::OverlayMoveDownRight => "overlay move down-right",
+        ShortcutAction::OverlayResizeUp => "overlay resize up",
+        ShortcutAction::OverlayResizeDown => "overlay resize down",
+        ShortcutAction::OverlayResizeLeft => "overlay resize left",
+        ShortcutAction::OverlayResizeRight => "overlay resize right",
+        ShortcutAction::OverlayClear => "overlay clear",
+        ShortcutAction::OverlayReset => "overlay reset",
+        ShortcutAction::OverlayCenter => "overlay center",
+        ShortcutAction::OverlaySnap(edge) => {
+            return format!("{} -> overlay snap {}", binding.key, edge.name());
+        }
+        ShortcutAction::AgentToggle(agent_id) => {
+            return format!("{} -> toggle agent {}", binding.key, agent_id);
+        }
+        ShortcutAction::AgentBroadcast(broadcast_action) => {
+            return format!("{} -> {} all agents", binding.key, broadcast_action);
+        }
+    };
+    format!("{} -> {}", binding.key, description)
+}
+
+/// Replaces every registered global shortcut with the bindings from `app_config`.
+/// Used at startup and whenever the shortcut config changes at runtime.
+#[cfg(desktop)]
+pub fn apply_shortcuts(app_handle: &AppHandle, app_config: &AppConfig) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
-    // Register all shortcuts
+    let shortcut_state = app_handle.state::<UnifiedShortcutState>();
+
+    app_handle
+        .global_shortcut()
+        .unregister_all()
+        .map_err(|e| format!("Failed to unregister shortcuts: {}", e))?;
+
+    let mut active_bindings = Vec::new();
     let mut registered_keys = Vec::new();
+    let mut statuses = Vec::new();
+    let mut conflicts = Vec::new();
+    // First binding to claim each parsed shortcut; later ones are skipped
+    let mut claimed: Vec<(tauri_plugin_global_shortcut::Shortcut, String)> = Vec::new();
 
-    for (shortcut, key, action) in shortcuts_to_register {
-        match app.global_shortcut().register(shortcut) {
-            Ok(_) => {
-                let description = match action {
-                    ShortcutAction::OverlayToggle => "overlay toggle",
-                    ShortcutAction::OverlayMoveUp => "overlay move up",
-                    ShortcutAction::OverlayMoveDown => "overlay move down",
-                    ShortcutAction::OverlayMoveLeft => "overlay move left",
-                    ShortcutAction::OverlayMoveRight => "overlay move right",
-                    ShortcutAction::OverlayResizeUp => "overlay resize up",
-                    ShortcutAction::OverlayResizeDown => "overlay resize down",
-                    ShortcutAction::OverlayResizeLeft => "overlay resize left",
-                    ShortcutAction::OverlayResizeRight => "overlay resize right",
-                    ShortcutAction::AgentToggle(agent_id) => {
-                        registered_keys.push(format!("{} -> toggle agent {}", key, agent_id));
-                        continue;
-                    }
-                };
+    // Bindings that are skipped before registration
+    let overlay_names: Vec<&str> = app_config
+        .shortcuts
+        .overlay_bindings()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    for (action, key) in app_config.shortcuts.all_bindings() {
+        let (state, error) =
+            if !app_config.overlay_enabled && overlay_names.contains(&action.as_str()) {
+                (
+                    ShortcutRegistrationState::Disabled,
+                    "Overlay disabled".to_string(),
+                )
+            } else if let Err(error) = parse_shortcut(&key) {
+                (ShortcutRegistrationState::Unparseable, error)
+            } else {
+                continue;
+            };
+        statuses.push(ShortcutStatus {
+            action,
+            key,
+            state,
+            error: Some(error),
+        });
+    }
 
-                log::info!("✓ Registered shortcut '{}' for {}", key, description);
-                registered_keys.push(format!("{} -> {}", key, description));
+    for binding in collect_bindings(app_config) {
+        let action = binding.action.name();
+        if let Some((_, owner)) = claimed.iter().find(|(s, _)| *s == binding.shortcut) {
+            let conflict = format!(
+                "{} ({}) conflicts with {}, which is already bound to the same shortcut",
+                action, binding.key, owner
+            );
+            log::warn!("✗ Skipping shortcut: {}", conflict);
+            statuses.push(ShortcutStatus {
+                action,
+                key: binding.key.clone(),
+                state: ShortcutRegistrationState::Conflict,
+                error: Some(conflict.clone()),
+            });
+            conflicts.push(conflict);
+            continue;
+        }
+        claimed.push((binding.shortcut, action.clone()));
+
+        let (state, error) = match app_handle.global_shortcut().register(binding.shortcut) {
+            Ok(_) => {
+                let description = describe_binding(&binding);
+                log::info!("✓ Registered shortcut {}", description);
+                registered_keys.push(description);
+                (ShortcutRegistrationState::Registered, None)
             }
             Err(e) => {
-                log::warn!("✗ Failed to register shortcut '{}': {}", key, e);
+                log::warn!("✗ Failed to register shortcut '{}': {}", binding.key, e);
+                (
+                    ShortcutRegistrationState::FailedToRegister,
+                    Some(e.to_string()),
+                )
             }
+        };
+        statuses.push(ShortcutStatus {
+            action,
+            key: binding.key.clone(),
+            state,
+            error,
+        });
+        if matches!(state, ShortcutRegistrationState::Registered) {
+            active_bindings.push(binding);
         }
     }
 
+    *shortcut_state.bindings.lock().unwrap() = active_bindings;
+    *shortcut_state.status.lock().unwrap() = statuses;
+    *shortcut_state.conflicts.lock().unwrap() = conflicts;
     // Update registered shortcuts state
     *shortcut_state.registered_shortcuts.lock().unwrap() = registered_keys;
 
@@ -664,3 +2155,283 @@ pub fn register_shortcuts_on_startup(
     );
     Ok(())
 }
+
+/// Re-registers the shortcuts from the in-memory config (no-op on mobile)
+pub fn refresh_shortcuts(app_handle: &AppHandle) -> Result<(), String> {
+    #[cfg(desktop)]
+    {
+        let shortcut_state = app_handle.state::<UnifiedShortcutState>();
+        let app_config = shortcut_state.config.lock().unwrap().clone();
+        apply_shortcuts(app_handle, &app_config)
+    }
+    #[cfg(not(desktop))]
+    {
+        let _ = app_handle;
+        Ok(())
+    }
+}
+
+// Main registration function - installs the plugin handler once at startup
+#[cfg(desktop)]
+pub fn register_shortcuts_on_startup(
+    app: &mut tauri::App,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use tauri_plugin_global_shortcut::ShortcutState;
+
+    // Register the single global shortcut handler; it looks bindings up in
+    // UnifiedShortcutState so they can be replaced without reinstalling it
+    app.handle().plugin(
+        tauri_plugin_global_shortcut::Builder::new()
+            .with_handler(move |app_handle, shortcut, event| {
+                if event.state() != ShortcutState::Pressed {
+                    return;
+                }
+
+                // Find which shortcut was pressed (lock released before running the action)
+                let binding = app_handle
+                    .state::<UnifiedShortcutState>()
+                    .bindings
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .find(|binding| &binding.shortcut == shortcut)
+                    .cloned();
+
+                if let Some(binding) = binding {
+                    app_handle
+                        .state::<UnifiedShortcutState>()
+                        .record_activation(&binding);
+
+                    // Emit shortcut-pressed event for visual feedback (before executing action)
+                    if let Err(e) = app_handle.emit("shortcut-pressed", &binding.key) {
+                        log::warn!("Failed to emit shortcut-pressed event: {}", e);
+                    }
+
+                    run_shortcut_action(app_handle, &binding.action);
+                }
+            })
+            .build(),
+    )?;
+
+    refresh_shortcuts(app.handle())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tauri_plugin_global_shortcut::{Code, Modifiers};
+
+    #[test]
+    fn storing_shortcuts_keeps_the_api_key() {
+        let settings_path =
+            std::env::temp_dir().join(format!("observer-settings-{}.json", uuid::Uuid::new_v4()));
+        let state = UnifiedShortcutState::new(AppConfig {
+            ollama_url: Some("http://localhost:11434".to_string()),
+            ollama_api_key: Some("secret".to_string()),
+            ..AppConfig::default()
+        });
+        let shortcuts = UnifiedShortcutConfig {
+            overlay_toggle: Some("Alt+O".to_string()),
+            ..UnifiedShortcutConfig::default()
+        };
+
+        let result = store_shortcut_config(&settings_path, &state, shortcuts);
+        let saved = std::fs::read_to_string(&settings_path);
+        let _ = std::fs::remove_file(&settings_path);
+        result.unwrap();
+
+        let in_memory = state.config.lock().unwrap().clone();
+        assert_eq!(in_memory.ollama_api_key.as_deref(), Some("secret"));
+        assert_eq!(in_memory.shortcuts.overlay_toggle.as_deref(), Some("Alt+O"));
+
+        let saved: AppConfig = serde_json::from_str(&saved.unwrap()).unwrap();
+        assert_eq!(saved.ollama_api_key.as_deref(), Some("secret"));
+        assert_eq!(saved.ollama_url.as_deref(), Some("http://localhost:11434"));
+        assert_eq!(saved.shortcuts.overlay_toggle.as_deref(), Some("Alt+O"));
+    }
+
+    #[test]
+    fn agent_shortcuts_round_trip_as_bare_keys_or_entries() {
+        let json = r#"{"plain":"Alt+1","scoped":{"key":"Alt+2","active_when_app":"Figma"}}"#;
+        let shortcuts: HashMap<String, AgentShortcut> = serde_json::from_str(json).unwrap();
+        assert_eq!(shortcuts["plain"], AgentShortcut::new("Alt+1".to_string()));
+        assert_eq!(shortcuts["scoped"].key, "Alt+2");
+        assert_eq!(
+            shortcuts["scoped"].active_when_app.as_deref(),
+            Some("Figma")
+        );
+
+        let value = serde_json::to_value(&shortcuts).unwrap();
+        assert_eq!(value["plain"], "Alt+1");
+        assert_eq!(value["scoped"]["active_when_app"], "Figma");
+    }
+
+    #[test]
+    fn storing_shortcuts_keeps_agent_conditions() {
+        let settings_path =
+            std::env::temp_dir().join(format!("observer-settings-{}.json", uuid::Uuid::new_v4()));
+        let mut config = AppConfig::default();
+        config.shortcuts.agent_shortcuts.insert(
+            "scoped".to_string(),
+            AgentShortcut {
+                key: "Alt+2".to_string(),
+                active_when_app: Some("Figma".to_string()),
+            },
+        );
+        let state = UnifiedShortcutState::new(config);
+        let mut shortcuts = UnifiedShortcutConfig::default();
+        shortcuts.agent_shortcuts.insert(
+            "scoped".to_string(),
+            AgentShortcut::new("Alt+3".to_string()),
+        );
+
+        let result = store_shortcut_config(&settings_path, &state, shortcuts);
+        let _ = std::fs::remove_file(&settings_path);
+        result.unwrap();
+
+        let config = state.config.lock().unwrap();
+        let scoped = &config.shortcuts.agent_shortcuts["scoped"];
+        assert_eq!(scoped.key, "Alt+3");
+        assert_eq!(scoped.active_when_app.as_deref(), Some("Figma"));
+    }
+
+    #[test]
+    fn importing_a_keymap_keeps_agent_conditions() {
+        let mut current = UnifiedShortcutConfig::default();
+        for (agent_id, key) in [("scoped", "Alt+2"), ("dropped", "Alt+4")] {
+            current.agent_shortcuts.insert(
+                agent_id.to_string(),
+                AgentShortcut {
+                    key: key.to_string(),
+                    active_when_app: Some("Figma".to_string()),
+                },
+            );
+        }
+        // "dropped" is no longer bound after the import
+        let keymap = serde_json::from_str(
+            r#"{"version":1,"bindings":[{"keys":"Alt+3","action":"agent:scoped"}]}"#,
+        )
+        .unwrap();
+        let mut imported = crate::keymap::from_keymap(&keymap).unwrap();
+
+        keep_agent_conditions(&mut imported, &current);
+
+        let scoped = &imported.agent_shortcuts["scoped"];
+        assert_eq!(scoped.key, "Alt+3");
+        assert_eq!(scoped.active_when_app.as_deref(), Some("Figma"));
+        assert!(!imported.agent_shortcuts.contains_key("dropped"));
+    }
+
+    #[test]
+    fn parses_numpad_keys() {
+        let cases = [
+            ("Numpad0", Code::Numpad0),
+            ("Numpad1", Code::Numpad1),
+            ("Numpad2", Code::Numpad2),
+            ("Numpad3", Code::Numpad3),
+            ("Numpad4", Code::Numpad4),
+            ("Numpad5", Code::Numpad5),
+            ("Numpad6", Code::Numpad6),
+            ("Numpad7", Code::Numpad7),
+            ("Numpad8", Code::Numpad8),
+            ("Numpad9", Code::Numpad9),
+            ("NumpadAdd", Code::NumpadAdd),
+            ("NumpadSubtract", Code::NumpadSubtract),
+            ("NumpadMultiply", Code::NumpadMultiply),
+            ("NumpadDivide", Code::NumpadDivide),
+            ("NumpadEnter", Code::NumpadEnter),
+            ("NumpadDecimal", Code::NumpadDecimal),
+        ];
+        for (token, code) in cases {
+            let shortcut = parse_shortcut(&format!("Ctrl+{}", token)).unwrap();
+            assert_eq!(shortcut.key, code, "{}", token);
+            assert_eq!(shortcut.mods, Modifiers::CONTROL, "{}", token);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_numpad_keys() {
+        assert!(parse_shortcut("Numpad10").is_err());
+        assert!(parse_shortcut("Ctrl+Numpad10").is_err());
+    }
+
+    #[test]
+    fn parses_bare_media_keys_without_modifiers() {
+        let shortcut = parse_shortcut("MediaPlayPause").unwrap();
+        assert_eq!(shortcut.key, Code::MediaPlayPause);
+        assert_eq!(shortcut.mods, Modifiers::empty());
+    }
+
+    #[test]
+    fn maps_cmd_or_ctrl_to_the_platform_modifier() {
+        let expected = if cfg!(target_os = "macos") {
+            Modifiers::SUPER
+        } else {
+            Modifiers::CONTROL
+        };
+        let shortcut = parse_shortcut("CmdOrCtrl+K").unwrap();
+        assert_eq!(shortcut.mods, expected);
+        assert_eq!(shortcut.key, Code::KeyK);
+
+        let shortcut = parse_shortcut("CmdOrCtrl+Shift+K").unwrap();
+        assert_eq!(shortcut.mods, expected | Modifiers::SHIFT);
+    }
+
+    #[test]
+    fn parses_extended_function_keys() {
+        let cases = [
+            ("F13", Code::F13),
+            ("F14", Code::F14),
+            ("F15", Code::F15),
+            ("F16", Code::F16),
+            ("F17", Code::F17),
+            ("F18", Code::F18),
+            ("F19", Code::F19),
+            ("F20", Code::F20),
+            ("F21", Code::F21),
+            ("F22", Code::F22),
+            ("F23", Code::F23),
+            ("F24", Code::F24),
+        ];
+        for (token, code) in cases {
+            let bare = parse_shortcut(token).unwrap();
+            assert_eq!(bare.key, code, "{}", token);
+            assert_eq!(bare.mods, Modifiers::empty(), "{}", token);
+
+            let combo = parse_shortcut(&format!("Alt+Shift+{}", token)).unwrap();
+            assert_eq!(combo.key, code, "{}", token);
+            assert_eq!(combo.mods, Modifiers::ALT | Modifiers::SHIFT, "{}", token);
+        }
+    }
+
+    #[test]
+    fn rejects_function_keys_past_f24() {
+        assert!(parse_shortcut("F25").is_err());
+        assert!(parse_shortcut("Ctrl+F25").is_err());
+    }
+
+    #[test]
+    fn parses_punctuation_by_name_and_symbol() {
+        let cases = [
+            ("Comma", ",", Code::Comma),
+            ("Period", ".", Code::Period),
+            ("Slash", "/", Code::Slash),
+            ("Backslash", "\\", Code::Backslash),
+            ("Semicolon", ";", Code::Semicolon),
+            ("Quote", "'", Code::Quote),
+            ("BracketLeft", "[", Code::BracketLeft),
+            ("BracketRight", "]", Code::BracketRight),
+            ("Minus", "-", Code::Minus),
+            ("Equal", "=", Code::Equal),
+            ("Backquote", "`", Code::Backquote),
+        ];
+        for (name, symbol, code) in cases {
+            let by_name = parse_shortcut(&format!("Ctrl+{}", name)).unwrap();
+            let by_symbol = parse_shortcut(&format!("Ctrl+{}", symbol)).unwrap();
+            assert_eq!(by_name.key, code, "{}", name);
+            assert_eq!(by_symbol.key, code, "{}", symbol);
+            assert_eq!(by_name, by_symbol, "{} / {}", name, symbol);
+        }
+    }
+}
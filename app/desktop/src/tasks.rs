@@ -0,0 +1,76 @@
+// In src-tauri/src/tasks.rs
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use tauri::async_runtime::JoinHandle;
+use tauri::State;
+
+/// Registry of named background tasks so they can be listed and cancelled
+#[derive(Default)]
+pub struct BackgroundTasks {
+    tasks: Mutex<HashMap<String, JoinHandle<()>>>,
+}
+
+impl BackgroundTasks {
+    /// Spawns `task` under `name`, aborting any task previously registered with that name
+    pub fn spawn<F>(&self, name: &str, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let handle = tauri::async_runtime::spawn(task);
+        if let Some(previous) = self.tasks.lock().unwrap().insert(name.to_string(), handle) {
+            log::info!("Replacing background task '{}'", name);
+            previous.abort();
+        }
+    }
+
+    /// Names of the tasks that are still running (finished tasks are pruned)
+    pub fn names(&self) -> Vec<String> {
+        let mut tasks = self.tasks.lock().unwrap();
+        tasks.retain(|_, handle| !handle.inner().is_finished());
+
+        let mut names: Vec<String> = tasks.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Aborts the named task, returning false if no such task is running
+    pub fn cancel(&self, name: &str) -> bool {
+        match self.tasks.lock().unwrap().remove(name) {
+            Some(handle) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Aborts every registered task (used on shutdown)
+    pub fn cancel_all(&self) {
+        for (name, handle) in self.tasks.lock().unwrap().drain() {
+            log::info!("Cancelling background task '{}'", name);
+            handle.abort();
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn list_background_tasks(
+    tasks: State<'_, BackgroundTasks>,
+) -> Result<Vec<String>, String> {
+    Ok(tasks.names())
+}
+
+#[tauri::command]
+pub async fn cancel_background_task(
+    name: String,
+    tasks: State<'_, BackgroundTasks>,
+) -> Result<(), String> {
+    log::info!("Cancelling background task '{}'", name);
+    if tasks.cancel(&name) {
+        Ok(())
+    } else {
+        Err(format!("No running background task named '{}'", name))
+    }
+}
@@ -0,0 +1,255 @@
+// In src-tauri/src/validation.rs
+
+use crate::shortcuts::{self, AppConfig, UnifiedShortcutState};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::State;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ConfigIssue {
+    severity: IssueSeverity,
+    // Config field (or shortcut action name) the issue refers to
+    field: String,
+    message: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ConfigValidationReport {
+    // False when at least one error was found; warnings alone keep it valid
+    valid: bool,
+    issues: Vec<ConfigIssue>,
+}
+
+impl ConfigValidationReport {
+    /// The errors as "field: message", ignoring warnings
+    pub fn error_messages(&self) -> Vec<String> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == IssueSeverity::Error)
+            .map(|issue| format!("{}: {}", issue.field, issue.message))
+            .collect()
+    }
+}
+
+struct Issues(Vec<ConfigIssue>);
+
+impl Issues {
+    fn error(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.push(IssueSeverity::Error, field.into(), message.into());
+    }
+
+    fn warning(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.push(IssueSeverity::Warning, field.into(), message.into());
+    }
+
+    fn push(&mut self, severity: IssueSeverity, field: String, message: String) {
+        self.0.push(ConfigIssue {
+            severity,
+            field,
+            message,
+        });
+    }
+}
+
+fn check_shortcuts(config: &AppConfig, issues: &mut Issues) {
+    // Keyed by the parsed shortcut so spellings of the same keys (e.g.
+    // CmdOrCtrl+K and Ctrl+K off macOS) count as one
+    let mut bindings_by_shortcut: HashMap<
+        tauri_plugin_global_shortcut::Shortcut,
+        Vec<(String, String)>,
+    > = HashMap::new();
+
+    for (action, key) in config.shortcuts.all_bindings() {
+        match shortcuts::normalize_shortcut(&key) {
+            Ok(normalized) => {
+                if !normalized.contains('+') {
+                    issues.warning(
+                        &action,
+                        format!(
+                            "{} has no modifier and will capture the key system-wide",
+                            key
+                        ),
+                    );
+                }
+                if let Some(shortcut) = shortcuts::parse_shortcut_string(&normalized) {
+                    bindings_by_shortcut
+                        .entry(shortcut)
+                        .or_default()
+                        .push((action, normalized));
+                }
+            }
+            Err(e) => issues.error(&action, e),
+        }
+    }
+
+    for mut bindings in bindings_by_shortcut.into_values() {
+        if bindings.len() > 1 {
+            bindings.sort();
+            let actions: Vec<&str> = bindings.iter().map(|(action, _)| action.as_str()).collect();
+            let mut keys: Vec<&str> = bindings.iter().map(|(_, key)| key.as_str()).collect();
+            keys.sort();
+            keys.dedup();
+            issues.error(
+                actions.join(", "),
+                format!("{} is bound to more than one action", keys.join(" / ")),
+            );
+        }
+    }
+}
+
+fn check_url(field: &str, url: &str, issues: &mut Issues) {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => {
+            if !matches!(parsed.scheme(), "http" | "https") {
+                issues.error(
+                    field,
+                    format!("Unsupported URL scheme: {}", parsed.scheme()),
+                );
+            }
+            if parsed.host_str().is_none() {
+                issues.error(field, format!("URL has no host: {}", url));
+            }
+            if parsed.port() == Some(0) {
+                issues.error(field, "Port must be between 1 and 65535");
+            }
+        }
+        Err(e) => issues.error(field, format!("Invalid URL '{}': {}", url, e)),
+    }
+}
+
+/// Runs every config check and collects all problems found
+pub fn validate(config: &AppConfig) -> ConfigValidationReport {
+    let mut issues = Issues(Vec::new());
+
+    check_shortcuts(config, &mut issues);
+
+    if let Some(url) = &config.ollama_url {
+        check_url("ollama_url", url, &mut issues);
+    }
+    for (name, profile) in &config.ollama_profiles {
+        if let Some(url) = &profile.url {
+            check_url(&format!("ollama_profiles.{}", name), url, &mut issues);
+        }
+    }
+    for (i, backend) in config.ollama_failover_urls.iter().enumerate() {
+        check_url(
+            &format!("ollama_failover_urls[{}]", i),
+            &backend.url,
+            &mut issues,
+        );
+    }
+
+    for (name, value) in &config.proxy_extra_headers {
+        if let Err(e) = crate::parse_extra_header(name, value) {
+            issues.error(format!("proxy_extra_headers.{}", name), e);
+        }
+    }
+
+    for (agent_id, shortcut) in &config.shortcuts.agent_shortcuts {
+        if shortcut.active_when_app.is_some() && shortcut.key.is_empty() {
+            issues.warning(
+                format!("shortcuts.agent_shortcuts.{}.active_when_app", agent_id),
+                "Condition has no effect because the agent has no shortcut",
+            );
+        }
+    }
+
+    for (agent_id, color) in &config.agent_colors {
+        if !crate::overlay::is_valid_hex_color(color) {
+            issues.error(
+                format!("agent_colors.{}", agent_id),
+                format!("Invalid hex color: {}", color),
+            );
+        }
+    }
+
+    if let Some(theme) = &config.theme {
+        if !crate::THEMES.contains(&theme.as_str()) {
+            issues.error("theme", format!("Invalid theme: {}", theme));
+        }
+    }
+
+    if let Some(anchor) = &config.overlay_anchor {
+        if !crate::overlay::OVERLAY_ANCHORS.contains(&anchor.as_str()) {
+            issues.error(
+                "overlay_anchor",
+                format!("Unknown overlay anchor: {}", anchor),
+            );
+        }
+    }
+
+    if let Some(quiet_hours) = &config.quiet_hours {
+        if let Err(e) = crate::notifications::validate_quiet_hours(quiet_hours) {
+            issues.error("quiet_hours", e);
+        }
+    }
+
+    if config
+        .click_allowed_agents
+        .as_ref()
+        .is_some_and(|a| a.is_empty())
+    {
+        issues.warning(
+            "click_allowed_agents",
+            "Allow-list is empty, so the input controls are denied for every agent",
+        );
+    }
+
+    if config.notification_dedupe_window_ms == Some(0) {
+        issues.warning(
+            "notification_dedupe_window_ms",
+            "A zero dedupe window never suppresses anything",
+        );
+    }
+
+    if config.sse_max_lifetime_secs == Some(0) {
+        issues.warning(
+            "sse_max_lifetime_secs",
+            "A zero lifetime closes every command stream as soon as it opens",
+        );
+    }
+
+    if !config.overlay_enabled
+        && config
+            .shortcuts
+            .overlay_bindings()
+            .iter()
+            .any(|(_, key)| key.is_some())
+    {
+        issues.warning(
+            "overlay_enabled",
+            "Overlay is disabled, so the overlay shortcuts are not registered",
+        );
+    }
+
+    ConfigValidationReport {
+        valid: !issues
+            .0
+            .iter()
+            .any(|issue| issue.severity == IssueSeverity::Error),
+        issues: issues.0,
+    }
+}
+
+/// Checks the whole in-memory config and reports all errors and warnings
+#[tauri::command]
+pub async fn validate_config(
+    shortcut_state: State<'_, UnifiedShortcutState>,
+) -> Result<ConfigValidationReport, String> {
+    let config = shortcut_state.config.lock().unwrap().clone();
+    let report = validate(&config);
+
+    log::info!(
+        "Config validation found {} issue(s), valid: {}",
+        report.issues.len(),
+        report.valid
+    );
+    Ok(report)
+}
@@ -0,0 +1,90 @@
+// In src-tauri/src/window_close.rs
+
+use crate::shortcuts::UnifiedShortcutState;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager, Window};
+use tokio::sync::oneshot;
+
+/// Windows waiting for the frontend to finish up before being hidden, by label
+#[derive(Default)]
+pub struct WindowCloseState {
+    pending: Mutex<HashMap<String, oneshot::Sender<()>>>,
+}
+
+fn hide_window(window: &Window) {
+    if let Err(e) = window.hide() {
+        log::warn!("Failed to hide window {}: {}", window.label(), e);
+    }
+}
+
+/// Hides a window instead of closing it. With a grace period configured, the
+/// window first gets a `window-close-requested` event and is hidden once the
+/// frontend calls ack_window_close, or when the grace period runs out
+pub fn handle_close_requested(window: &Window) {
+    let grace_period_ms = window
+        .state::<UnifiedShortcutState>()
+        .config
+        .lock()
+        .unwrap()
+        .close_grace_period_ms
+        .unwrap_or(0);
+    if grace_period_ms == 0 {
+        hide_window(window);
+        return;
+    }
+
+    let label = window.label().to_string();
+    let (tx, rx) = oneshot::channel();
+    {
+        let close_state = window.state::<WindowCloseState>();
+        let mut pending = close_state.pending.lock().unwrap();
+        // A second close request while one is pending keeps the first deadline
+        if pending.contains_key(&label) {
+            return;
+        }
+        pending.insert(label.clone(), tx);
+    }
+
+    if let Err(e) = window.emit_to(label.as_str(), "window-close-requested", grace_period_ms) {
+        log::warn!("Failed to emit window-close-requested event: {}", e);
+    }
+
+    let window = window.clone();
+    tauri::async_runtime::spawn(async move {
+        match tokio::time::timeout(Duration::from_millis(grace_period_ms), rx).await {
+            Ok(_) => log::info!("Window {} acknowledged close", label),
+            Err(_) => log::warn!(
+                "Window {} did not acknowledge close within {}ms, hiding anyway",
+                label,
+                grace_period_ms
+            ),
+        }
+        window
+            .state::<WindowCloseState>()
+            .pending
+            .lock()
+            .unwrap()
+            .remove(&label);
+        hide_window(&window);
+    });
+}
+
+/// Tells the backend the calling window has flushed its state and can be hidden
+#[tauri::command]
+pub async fn ack_window_close(window: Window) -> Result<(), String> {
+    let sender = window
+        .state::<WindowCloseState>()
+        .pending
+        .lock()
+        .unwrap()
+        .remove(window.label());
+    match sender {
+        Some(sender) => {
+            let _ = sender.send(());
+            Ok(())
+        }
+        None => Err(format!("No close pending for window {}", window.label())),
+    }
+}
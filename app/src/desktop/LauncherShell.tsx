@@ -236,8 +236,13 @@ function LauncherShell() {
         resize_right: unifiedConfig.overlay_resize_right || ''
       });
       
-      // Set agent shortcuts from unified config - no need to fetch agents from backend
-      setAgentShortcuts(unifiedConfig.agent_shortcuts || {});
+      // Set agent shortcuts from unified config - no need to fetch agents from backend.
+      // Entries with an app condition are objects; only the key is edited here and
+      // the backend keeps the condition on save
+      const agentShortcutEntries: Record<string, string | { key: string }> = unifiedConfig.agent_shortcuts || {};
+      setAgentShortcuts(Object.fromEntries(
+        Object.entries(agentShortcutEntries).map(([id, entry]) => [id, typeof entry === 'string' ? entry : entry.key])
+      ));
       // Generate available agents from the shortcuts that are configured
       const agentIds = Object.keys(unifiedConfig.agent_shortcuts || {});
       const agents = agentIds.map(id => ({ id, name: id })); // Use ID as name for now